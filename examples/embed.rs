@@ -0,0 +1,70 @@
+//! Run:
+//!
+//! ```bash
+//! cargo run --example embed
+//! ```
+//!
+//! Demonstrates embedding a libuv loop in an external epoll-based reactor, which is how you would
+//! integrate libuv with another event loop. This example only works on Linux.
+
+extern crate libuv;
+#[cfg(target_os = "linux")]
+use libuv::{prelude::*, TimerHandle};
+
+#[cfg(target_os = "linux")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut r#loop = Loop::default()?;
+
+    // some work for the loop to do: tick 5 times, then close the timer
+    let mut count = 0;
+    let mut timer = r#loop.timer()?;
+    timer.start(100, 100, move |mut handle: TimerHandle| {
+        count += 1;
+        println!("Tick {}", count);
+        if count >= 5 {
+            handle.close(());
+        }
+    })?;
+
+    // the "external" reactor: an epoll instance watching the loop's backend fd
+    let epfd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+    if epfd < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let mut event = libc::epoll_event {
+        events: libc::EPOLLIN as _,
+        u64: 0,
+    };
+    let ret =
+        unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, r#loop.backend_fd(), &mut event) };
+    if ret < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    // process anything that's already pending, then wait for the loop to become ready and run it
+    // until it runs out of work
+    let mut alive = r#loop.run_ready()?;
+    while alive {
+        let timeout = r#loop.backend_timeout();
+        let mut events: [libc::epoll_event; 1] = unsafe { std::mem::zeroed() };
+        let ret = unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 1, timeout) };
+        if ret < 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(err.into());
+            }
+        }
+
+        alive = r#loop.run_ready()?;
+    }
+
+    unsafe { libc::close(epfd) };
+    println!("Loop is done");
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    println!("This example requires epoll, which is only available on Linux.");
+}
@@ -6,7 +6,7 @@
 
 extern crate libuv;
 use libuv::prelude::*;
-use libuv::{Buf, ReadonlyBuf, UdpBindFlags, UdpHandle};
+use libuv::{Buf, ReadonlyBuf, UdpBindFlags, UdpHandle, UdpRecvFlags};
 use rand::prelude::*;
 use std::net::{Ipv4Addr, SocketAddr};
 
@@ -18,15 +18,22 @@ fn on_read(
     mut handle: UdpHandle,
     nread: libuv::Result<usize>,
     mut buf: ReadonlyBuf,
-    addr: SocketAddr,
-    _flags: UdpBindFlags,
+    addr: Option<SocketAddr>,
+    _flags: UdpRecvFlags,
 ) {
     match nread {
-        Ok(_) => {
-            let ip = &buf[16..20];
-            println!("Recv from {}", addr);
-            println!("Offered IP {}.{}.{}.{}", ip[0], ip[1], ip[2], ip[3]);
-        }
+        Ok(_) => match addr {
+            Some(addr) => {
+                let ip = &buf[16..20];
+                println!("Recv from {}", addr);
+                println!("Offered IP {}.{}.{}.{}", ip[0], ip[1], ip[2], ip[3]);
+            }
+            None => {
+                // nothing more to read
+                buf.dealloc();
+                return;
+            }
+        },
         Err(e) => {
             eprintln!("Read error {}", e);
         }
@@ -14,7 +14,12 @@ fn on_write(req: FsReq, file: libuv::File, mut buf: Buf) {
     if let Err(e) = req.result() {
         eprintln!("Write error: {}", e);
         buf.destroy();
-    } else if let Err(e) = req
+        return;
+    }
+
+    // the whole buffer is available for the next read
+    let _ = buf.resize(buf.capacity());
+    if let Err(e) = req
         .r#loop()
         .fs_read(file, &[buf], -1, move |req| on_read(file, req, buf))
     {
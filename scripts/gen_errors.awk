@@ -1,8 +1,15 @@
-# run like awk -f scripts/gen_errors.awk target/rls/debug/build/libuv-sys2-02275bbf285602f5/out/bindings.rs > src/error.inc.rs
+# run like awk -f scripts/gen_errors.awk path/to/libuv/include/uv.h > src/error.inc.rs
+#
+# The error names are read from UV_ERRNO_MAP in uv.h, which lists every UV_E* code, rather than
+# from the generated bindings.
 
-/^pub const uv_errno_t_UV_/ {
-  name = substr($3, 15, length($3) - 15);
-  errors[name] = substr($6, 1, length($6) - 1)
+/^  XX\((E[A-Z0-9_]+|UNKNOWN), / {
+  name = substr($1, 4, length($1) - 4);
+  errors[name] = name
+}
+
+/^  UV_ERRNO_MAX = / {
+  errors["ERRNO_MAX"] = "ERRNO_MAX"
 }
 
 END {
@@ -27,11 +34,13 @@ END {
   print "}\n";
 
   print "impl Error {";
-  print indent "fn code(&self) -> uv::uv_errno_t {";
-  print indent indent "match self {";
+  print indent "/// The libuv error code, which is a negative errno value.";
+  print indent "pub fn code(&self) -> i32 {";
+  print indent indent "let code = match self {";
   for (i = 1; i <= nerrors; i++)
     print indent indent indent "Error::" errors[i] " => uv::uv_errno_t_UV_" errors[i] ",";
-  print indent indent "}";
+  print indent indent "};";
+  print indent indent "code as _";
   print indent "}";
   print "}"
 }
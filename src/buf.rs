@@ -1,4 +1,4 @@
-use crate::{FromInner, Inner, IntoInner};
+use crate::{FromInner, Inner};
 use std::borrow::Cow;
 use std::ffi::CStr;
 use std::ops::{
@@ -50,7 +50,7 @@ pub struct ReadonlyBuf {
 impl ReadonlyBuf {
     /// Returns true if the internal buffer is initialized
     pub fn is_allocated(&self) -> bool {
-        unsafe { !(*self.buf).base.is_null() }
+        !self.buf.is_null() && unsafe { !(*self.buf).base.is_null() }
     }
 
     /// Deallocate the internal buffer, but leave the Buf intact. Even though this is a "readonly"
@@ -59,6 +59,10 @@ impl ReadonlyBuf {
     /// callback to create a Buf, then passes that Buf to the read callback as a ReadonlyBuf. You
     /// could run dealloc() in the read callback to deallocate the internal buffer - the allocate
     /// callback takes ownership of the actual Buf struct, so you don't need to worry about that.
+    ///
+    /// The size of the internal buffer is taken to be the ReadonlyBuf's length. This is always true
+    /// of the buffers passed to read callbacks, which are given the full capacity of the Buf that
+    /// the allocate callback returned.
     pub fn dealloc(&mut self) {
         unsafe {
             if self.is_allocated() {
@@ -70,6 +74,19 @@ impl ReadonlyBuf {
         }
     }
 
+    /// Take ownership of the internal buffer, returning a new Buf that points to it. This is useful
+    /// in a read callback: the buffer that was allocated by the allocate callback can be handed
+    /// off to StreamTrait::write_buf() without copying it. The returned Buf is then responsible
+    /// for the internal buffer, so this ReadonlyBuf must not be used (or dealloc()'d) afterwards.
+    pub fn into_buf(self) -> Buf {
+        if self.buf.is_null() {
+            Buf::from_parts(std::ptr::null_mut(), 0, 0)
+        } else {
+            let len = unsafe { (*self.buf).len as _ };
+            Buf::from_parts(unsafe { (*self.buf).base }, len, len)
+        }
+    }
+
     /// Convert the Buf to a CStr. Returns an error if the Buf is empty. Data contained within the
     /// ReadonlyBuf must be null-terminated or this will fail!
     pub fn as_c_str(&self) -> Result<&'_ CStr, EmptyBufError> {
@@ -106,6 +123,33 @@ impl ReadonlyBuf {
             }
         }
     }
+
+    /// Returns the first len bytes of the Buf as a slice, such as the nread bytes that were passed
+    /// to a read callback along with the Buf. len is clamped to the size of the internal buffer,
+    /// and an empty slice is returned if the Buf is not allocated.
+    pub fn as_slice(&self, len: usize) -> &[u8] {
+        unsafe {
+            if self.is_allocated() {
+                let len = len.min((*self.buf).len as _);
+                std::slice::from_raw_parts((*self.buf).base as _, len)
+            } else {
+                &[]
+            }
+        }
+    }
+
+    /// Copies the first len bytes of the Buf into a new Vec. len is clamped to the size of the
+    /// internal buffer, as with as_slice().
+    pub fn to_vec(&self, len: usize) -> Vec<u8> {
+        self.as_slice(len).to_vec()
+    }
+
+    /// Returns the first len bytes of the Buf as a &str, or an error if they are not valid utf8.
+    /// len is clamped to the size of the internal buffer, as with as_slice(). Unlike to_str(),
+    /// an empty Buf results in an empty string rather than an error.
+    pub fn as_str(&self, len: usize) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(self.as_slice(len))
+    }
 }
 
 impl FromInner<*const uv_buf_t> for ReadonlyBuf {
@@ -164,10 +208,15 @@ where
         panic!("Buf index starts at {} but ends at {}", start, end);
     }
 
-    if len <= end {
+    if len < end {
         panic!("index {} out of range for Buf of length {}", end, len);
     }
 
+    // an empty range of an unallocated Buf: base is null, which from_raw_parts does not allow
+    if !buf.is_allocated() {
+        return &[];
+    }
+
     unsafe { std::slice::from_raw_parts((*buf.buf).base.add(start) as *const u8, end - start) }
 }
 
@@ -219,13 +268,48 @@ impl Index<RangeToInclusive<usize>> for ReadonlyBuf {
     }
 }
 
+/// The heap allocation behind a Buf: the uv_buf_t, followed by the size of the internal buffer it
+/// points to. uv_buf_t is the first field, so a pointer to the container is also a pointer to the
+/// uv_buf_t that can be handed to libuv.
+#[repr(C)]
+struct BufContainer {
+    buf: uv_buf_t,
+    capacity: usize,
+}
+
 /// Buffer data type.
+///
+/// A Buf is a pointer to a heap-allocated uv_buf_t, which in turn points to the internal buffer.
+/// The uv_buf_t's len is the number of bytes in use, which can be changed with resize() up to the
+/// capacity() of the internal buffer, so a single allocation can be reused for many writes.
+/// Because Buf is Copy, it is never freed automatically: whoever holds the Buf owns both
+/// allocations and must release them with destroy() (or into_vec()), unless ownership is passed
+/// on. Functions that take a Buf by value, such as StreamTrait::write_buf(), take ownership and
+/// free it once libuv is done with it. When a Buf is returned from an allocate callback, libuv
+/// takes the internal buffer and the Buf struct itself is freed; the internal buffer is then
+/// handed to the read callback as a ReadonlyBuf and must be freed there.
 #[derive(Clone, Copy)]
 pub struct Buf {
     buf: *mut uv_buf_t,
 }
 
 impl Buf {
+    /// Wraps an internal buffer with the given capacity, of which the first len bytes are in use,
+    /// in a new Buf.
+    fn from_parts(base: *mut std::os::raw::c_char, len: usize, capacity: usize) -> Buf {
+        let container = Box::new(BufContainer {
+            buf: unsafe { uv_buf_init(base, len as _) },
+            capacity,
+        });
+        Buf {
+            buf: Box::into_raw(container) as _,
+        }
+    }
+
+    fn container(&self) -> *mut BufContainer {
+        self.buf as _
+    }
+
     fn alloc(size: usize) -> crate::Result<*mut std::os::raw::c_char> {
         let layout = layout(size)?;
         let ptr = unsafe { std::alloc::alloc(layout) as *mut std::os::raw::c_char };
@@ -251,15 +335,83 @@ impl Buf {
             base.add(len).write(0);
         }
 
-        let buf = Box::new(unsafe { uv_buf_init(base, buflen as _) });
-        Ok(Box::into_raw(buf).into_inner())
+        Ok(Buf::from_parts(base, buflen, buflen))
     }
 
-    /// Create a Buf with the given capacity - the memory is not initialized
+    /// Create a Buf with the given capacity - the memory is not initialized. The length of the Buf
+    /// is initially its capacity.
     pub fn with_capacity(size: usize) -> crate::Result<Buf> {
-        let base = Buf::alloc(size)?;
-        let buf = Box::new(unsafe { uv_buf_init(base, size as _) });
-        Ok(Box::into_raw(buf).into_inner())
+        let base = if size == 0 {
+            std::ptr::null_mut()
+        } else {
+            Buf::alloc(size)?
+        };
+        Ok(Buf::from_parts(base, size, size))
+    }
+
+    /// Create a Buf that takes ownership of the Vec's allocation, without copying or reallocating
+    /// it. The length of the Buf is the length of the Vec, and its capacity is the capacity of the
+    /// Vec. The Buf is then responsible for the memory: free it with destroy(), or turn it back
+    /// into a Vec with into_vec().
+    pub fn from_vec(v: Vec<u8>) -> Buf {
+        let mut v = std::mem::ManuallyDrop::new(v);
+        let len = v.len();
+        let capacity = v.capacity();
+        if capacity == 0 {
+            // an empty Vec has a dangling pointer rather than an allocation
+            return Buf::from_parts(std::ptr::null_mut(), 0, 0);
+        }
+        Buf::from_parts(v.as_mut_ptr() as _, len, capacity)
+    }
+
+    /// Convert the Buf into a Vec that takes ownership of its internal buffer, without copying it.
+    /// The Vec's length is the length of the Buf and its capacity is the capacity of the Buf. The
+    /// Buf itself is freed, so neither this Buf nor any copies of it may be used afterwards.
+    /// Dropping the Vec frees the memory.
+    ///
+    /// Note that a Buf created by with_capacity() is not initialized, so neither is the Vec until
+    /// data has been written to it.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        if self.buf.is_null() {
+            return Vec::new();
+        }
+
+        let v = unsafe {
+            if self.is_allocated() {
+                Vec::from_raw_parts(
+                    (*self.buf).base as *mut u8,
+                    (*self.buf).len as _,
+                    self.capacity(),
+                )
+            } else {
+                Vec::new()
+            }
+        };
+        self.destroy_container();
+        v
+    }
+
+    /// Returns the len() bytes of the internal buffer that are in use as a slice. This may include
+    /// uninitialized memory if the Buf was created with with_capacity().
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe {
+            if self.is_allocated() {
+                std::slice::from_raw_parts((*self.buf).base as _, (*self.buf).len as _)
+            } else {
+                &[]
+            }
+        }
+    }
+
+    /// Returns the contents of the internal buffer as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe {
+            if self.is_allocated() {
+                std::slice::from_raw_parts_mut((*self.buf).base as _, (*self.buf).len as _)
+            } else {
+                &mut []
+            }
+        }
     }
 
     /// Create a duplicate of this Buf - if the optional size parameter is None, the new Buf will
@@ -272,9 +424,7 @@ impl Buf {
             if let Some(s) = size {
                 return Buf::with_capacity(s);
             }
-            return Ok(Buf {
-                buf: std::ptr::null_mut(),
-            });
+            return Ok(Buf::from_parts(std::ptr::null_mut(), 0, 0));
         }
 
         let len = if let Some(s) = size {
@@ -290,32 +440,75 @@ impl Buf {
 
     /// Returns true if the internal buffer is initialized
     pub fn is_allocated(&self) -> bool {
-        unsafe { !(*self.buf).base.is_null() }
+        !self.buf.is_null() && unsafe { !(*self.buf).base.is_null() }
     }
 
-    /// Resizes the internal buffer
-    pub fn resize(&mut self, size: usize) -> crate::Result<()> {
+    /// Returns the number of bytes of the internal buffer that are in use, or 0 if it is not
+    /// allocated. This is what will be written when the Buf is passed to a write function.
+    pub fn len(&self) -> usize {
+        if self.buf.is_null() {
+            0
+        } else {
+            unsafe { (*self.buf).len as _ }
+        }
+    }
+
+    /// Returns true if the Buf has a length of 0.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the size of the internal buffer, which is the largest len() that resize() allows.
+    pub fn capacity(&self) -> usize {
+        if self.buf.is_null() {
+            0
+        } else {
+            unsafe { (*self.container()).capacity }
+        }
+    }
+
+    /// Changes the number of bytes of the internal buffer that are in use, without reallocating
+    /// it. Returns EINVAL if len is larger than capacity().
+    pub fn resize(&mut self, len: usize) -> crate::Result<()> {
+        if len > self.capacity() {
+            return Err(crate::Error::EINVAL);
+        }
+        if !self.buf.is_null() {
+            unsafe { (*self.buf).len = len as _ };
+        }
+        Ok(())
+    }
+
+    /// Reallocates the internal buffer with the given capacity, which also becomes its length. The
+    /// existing data is preserved up to the smaller of the old and new capacities.
+    fn reallocate(&mut self, capacity: usize) -> crate::Result<()> {
+        if capacity == 0 {
+            self.dealloc();
+            return Ok(());
+        }
+
         if self.is_allocated() {
-            let len = unsafe { (*self.buf).len as _ };
-            if len != size {
-                let (alloc_size, _) = calc_alloc_size_alignment(size)?;
-                let layout = layout(len)?;
+            let old_capacity = self.capacity();
+            if old_capacity != capacity {
+                let (alloc_size, _) = calc_alloc_size_alignment(capacity)?;
+                let layout = layout(old_capacity)?;
                 let ptr = unsafe { std::alloc::realloc((*self.buf).base as _, layout, alloc_size) };
                 if ptr.is_null() {
                     return Err(crate::Error::ENOMEM);
                 }
                 unsafe {
                     (*self.buf).base = ptr as _;
-                    (*self.buf).len = alloc_size as _;
+                    (*self.container()).capacity = alloc_size;
                 }
             }
         } else {
-            let base = Buf::alloc(size)?;
+            let base = Buf::alloc(capacity)?;
             unsafe {
                 (*self.buf).base = base as _;
-                (*self.buf).len = size as _;
+                (*self.container()).capacity = capacity;
             }
         }
+        unsafe { (*self.buf).len = capacity as _ };
         Ok(())
     }
 
@@ -328,7 +521,7 @@ impl Buf {
 
         let other_len = unsafe { (*other.buf).len as _ };
         if !self.is_allocated() {
-            self.resize(other_len)?;
+            self.reallocate(other_len)?;
         }
 
         let my_len = unsafe { (*self.buf).len as usize };
@@ -342,15 +535,33 @@ impl Buf {
         Ok(())
     }
 
+    /// Copies as much of the given data as will fit into the internal buffer, starting at the
+    /// beginning of the buffer, sets len() to the number of bytes copied, and returns it. Data that
+    /// does not fit is ignored, so this never writes past capacity(). This allows a single
+    /// allocation to be refilled for many writes, for example when building protocol frames.
+    pub fn copy_from_slice(&mut self, data: &[u8]) -> usize {
+        let len = self.capacity().min(data.len());
+        if len > 0 {
+            unsafe {
+                (*self.buf)
+                    .base
+                    .copy_from_nonoverlapping(data.as_ptr() as _, len)
+            };
+        }
+        let _ = self.resize(len);
+        len
+    }
+
     /// Deallocate the internal buffer, but leave the Buf intact.
     pub fn dealloc(&mut self) {
         unsafe {
             if self.is_allocated() {
-                let len = (*self.buf).len as _;
-                if let Ok(layout) = layout(len) {
+                let capacity = self.capacity();
+                if let Ok(layout) = layout(capacity) {
                     std::alloc::dealloc((*self.buf).base as _, layout);
                     (*self.buf).base = std::ptr::null_mut();
                     (*self.buf).len = 0;
+                    (*self.container()).capacity = 0;
                 }
             }
         }
@@ -358,7 +569,7 @@ impl Buf {
 
     /// Deallocates the Buf struct, leaving the internal buffer alone. This is used by alloc_cb.
     pub(crate) fn destroy_container(&mut self) {
-        std::mem::drop(unsafe { Box::from_raw(self.buf) });
+        std::mem::drop(unsafe { Box::from_raw(self.container()) });
     }
 
     /// Deallocates the internal buffer *and* the Buf
@@ -368,9 +579,16 @@ impl Buf {
     }
 }
 
+/// Creates a new Buf that takes ownership of the internal buffer the uv_buf_t points to, which
+/// must have been allocated the same way as a Buf's. The uv_buf_t itself is copied, not owned.
 impl FromInner<*mut uv_buf_t> for Buf {
     fn from_inner(buf: *mut uv_buf_t) -> Buf {
-        Buf { buf }
+        if buf.is_null() {
+            Buf::from_parts(std::ptr::null_mut(), 0, 0)
+        } else {
+            let len = unsafe { (*buf).len as _ };
+            Buf::from_parts(unsafe { (*buf).base }, len, len)
+        }
     }
 }
 
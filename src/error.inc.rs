@@ -51,6 +51,7 @@ pub enum Error {
     ENETUNREACH,
     ENFILE,
     ENOBUFS,
+    ENODATA,
     ENODEV,
     ENOENT,
     ENOMEM,
@@ -66,6 +67,7 @@ pub enum Error {
     ENOTTY,
     ENXIO,
     EOF,
+    EOVERFLOW,
     EPERM,
     EPIPE,
     EPROTO,
@@ -76,10 +78,12 @@ pub enum Error {
     EROFS,
     ERRNO_MAX,
     ESHUTDOWN,
+    ESOCKTNOSUPPORT,
     ESPIPE,
     ESRCH,
     ETIMEDOUT,
     ETXTBSY,
+    EUNATCH,
     EXDEV,
     UNKNOWN,
 }
@@ -137,6 +141,7 @@ impl crate::FromInner<uv::uv_errno_t> for Error {
             uv::uv_errno_t_UV_ENETUNREACH => Error::ENETUNREACH,
             uv::uv_errno_t_UV_ENFILE => Error::ENFILE,
             uv::uv_errno_t_UV_ENOBUFS => Error::ENOBUFS,
+            uv::uv_errno_t_UV_ENODATA => Error::ENODATA,
             uv::uv_errno_t_UV_ENODEV => Error::ENODEV,
             uv::uv_errno_t_UV_ENOENT => Error::ENOENT,
             uv::uv_errno_t_UV_ENOMEM => Error::ENOMEM,
@@ -152,6 +157,7 @@ impl crate::FromInner<uv::uv_errno_t> for Error {
             uv::uv_errno_t_UV_ENOTTY => Error::ENOTTY,
             uv::uv_errno_t_UV_ENXIO => Error::ENXIO,
             uv::uv_errno_t_UV_EOF => Error::EOF,
+            uv::uv_errno_t_UV_EOVERFLOW => Error::EOVERFLOW,
             uv::uv_errno_t_UV_EPERM => Error::EPERM,
             uv::uv_errno_t_UV_EPIPE => Error::EPIPE,
             uv::uv_errno_t_UV_EPROTO => Error::EPROTO,
@@ -162,10 +168,12 @@ impl crate::FromInner<uv::uv_errno_t> for Error {
             uv::uv_errno_t_UV_EROFS => Error::EROFS,
             uv::uv_errno_t_UV_ERRNO_MAX => Error::ERRNO_MAX,
             uv::uv_errno_t_UV_ESHUTDOWN => Error::ESHUTDOWN,
+            uv::uv_errno_t_UV_ESOCKTNOSUPPORT => Error::ESOCKTNOSUPPORT,
             uv::uv_errno_t_UV_ESPIPE => Error::ESPIPE,
             uv::uv_errno_t_UV_ESRCH => Error::ESRCH,
             uv::uv_errno_t_UV_ETIMEDOUT => Error::ETIMEDOUT,
             uv::uv_errno_t_UV_ETXTBSY => Error::ETXTBSY,
+            uv::uv_errno_t_UV_EUNATCH => Error::EUNATCH,
             uv::uv_errno_t_UV_EXDEV => Error::EXDEV,
             uv::uv_errno_t_UV_UNKNOWN => Error::UNKNOWN,
             _ => Error::UNKNOWN,
@@ -174,8 +182,9 @@ impl crate::FromInner<uv::uv_errno_t> for Error {
 }
 
 impl Error {
-    fn code(&self) -> uv::uv_errno_t {
-        match self {
+    /// The libuv error code, which is a negative errno value.
+    pub fn code(&self) -> i32 {
+        let code = match self {
             Error::E2BIG => uv::uv_errno_t_UV_E2BIG,
             Error::EACCES => uv::uv_errno_t_UV_EACCES,
             Error::EADDRINUSE => uv::uv_errno_t_UV_EADDRINUSE,
@@ -226,6 +235,7 @@ impl Error {
             Error::ENETUNREACH => uv::uv_errno_t_UV_ENETUNREACH,
             Error::ENFILE => uv::uv_errno_t_UV_ENFILE,
             Error::ENOBUFS => uv::uv_errno_t_UV_ENOBUFS,
+            Error::ENODATA => uv::uv_errno_t_UV_ENODATA,
             Error::ENODEV => uv::uv_errno_t_UV_ENODEV,
             Error::ENOENT => uv::uv_errno_t_UV_ENOENT,
             Error::ENOMEM => uv::uv_errno_t_UV_ENOMEM,
@@ -241,6 +251,7 @@ impl Error {
             Error::ENOTTY => uv::uv_errno_t_UV_ENOTTY,
             Error::ENXIO => uv::uv_errno_t_UV_ENXIO,
             Error::EOF => uv::uv_errno_t_UV_EOF,
+            Error::EOVERFLOW => uv::uv_errno_t_UV_EOVERFLOW,
             Error::EPERM => uv::uv_errno_t_UV_EPERM,
             Error::EPIPE => uv::uv_errno_t_UV_EPIPE,
             Error::EPROTO => uv::uv_errno_t_UV_EPROTO,
@@ -251,12 +262,15 @@ impl Error {
             Error::EROFS => uv::uv_errno_t_UV_EROFS,
             Error::ERRNO_MAX => uv::uv_errno_t_UV_ERRNO_MAX,
             Error::ESHUTDOWN => uv::uv_errno_t_UV_ESHUTDOWN,
+            Error::ESOCKTNOSUPPORT => uv::uv_errno_t_UV_ESOCKTNOSUPPORT,
             Error::ESPIPE => uv::uv_errno_t_UV_ESPIPE,
             Error::ESRCH => uv::uv_errno_t_UV_ESRCH,
             Error::ETIMEDOUT => uv::uv_errno_t_UV_ETIMEDOUT,
             Error::ETXTBSY => uv::uv_errno_t_UV_ETXTBSY,
+            Error::EUNATCH => uv::uv_errno_t_UV_EUNATCH,
             Error::EXDEV => uv::uv_errno_t_UV_EXDEV,
             Error::UNKNOWN => uv::uv_errno_t_UV_UNKNOWN,
-        }
+        };
+        code as _
     }
 }
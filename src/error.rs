@@ -1,27 +1,47 @@
 include!("./error.inc.rs");
 
+use crate::FromInner;
 use std::ffi::CStr;
 use std::fmt::{Display, Formatter};
-use uv::{uv_err_name, uv_strerror};
+use std::io::ErrorKind;
+use uv::{uv_err_name_r, uv_strerror_r, uv_translate_sys_error};
 
 impl Error {
-    /// The name of the error.
+    /// The name of the error. This uses the reentrant uv_err_name_r(), so it is safe to call from
+    /// any thread.
     pub fn name(&self) -> String {
+        let mut buf = [0 as std::os::raw::c_char; 64];
         unsafe {
-            CStr::from_ptr(uv_err_name(self.code() as _))
-                .to_string_lossy()
-                .into_owned()
+            CStr::from_ptr(uv_err_name_r(
+                self.code() as _,
+                buf.as_mut_ptr(),
+                buf.len() as _,
+            ))
+            .to_string_lossy()
+            .into_owned()
         }
     }
 
-    /// A message for the error.
+    /// A message for the error. This uses the reentrant uv_strerror_r(), so it is safe to call
+    /// from any thread.
     pub fn message(&self) -> String {
+        let mut buf = [0 as std::os::raw::c_char; 256];
         unsafe {
-            CStr::from_ptr(uv_strerror(self.code() as _))
-                .to_string_lossy()
-                .into_owned()
+            CStr::from_ptr(uv_strerror_r(
+                self.code() as _,
+                buf.as_mut_ptr(),
+                buf.len() as _,
+            ))
+            .to_string_lossy()
+            .into_owned()
         }
     }
+
+    /// Translates the last OS error (errno on Unix, GetLastError() on Windows) into an Error.
+    pub(crate) fn last_os_error() -> Error {
+        let sys_errno = std::io::Error::last_os_error().raw_os_error().unwrap_or(0);
+        Error::from_inner(unsafe { uv_translate_sys_error(sys_errno) } as uv::uv_errno_t)
+    }
 }
 
 impl Display for Error {
@@ -32,6 +52,75 @@ impl Display for Error {
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// The std::io::ErrorKind that best describes this error, or ErrorKind::Other if there isn't
+    /// a good match.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::ENOENT => ErrorKind::NotFound,
+            Error::EACCES | Error::EPERM => ErrorKind::PermissionDenied,
+            Error::ECONNREFUSED => ErrorKind::ConnectionRefused,
+            Error::ECONNRESET => ErrorKind::ConnectionReset,
+            Error::ECONNABORTED => ErrorKind::ConnectionAborted,
+            Error::ENOTCONN => ErrorKind::NotConnected,
+            Error::EADDRINUSE => ErrorKind::AddrInUse,
+            Error::EADDRNOTAVAIL => ErrorKind::AddrNotAvailable,
+            Error::EPIPE => ErrorKind::BrokenPipe,
+            Error::EEXIST => ErrorKind::AlreadyExists,
+            Error::EAGAIN => ErrorKind::WouldBlock,
+            Error::EINVAL => ErrorKind::InvalidInput,
+            Error::ETIMEDOUT => ErrorKind::TimedOut,
+            Error::EINTR => ErrorKind::Interrupted,
+            Error::EOF => ErrorKind::UnexpectedEof,
+            Error::ENOMEM => ErrorKind::OutOfMemory,
+            Error::ENOTSUP | Error::ENOSYS => ErrorKind::Unsupported,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// Converts to a std::io::Error with the matching ErrorKind. The original Error is kept as the
+/// inner error, so it can be recovered exactly by converting back.
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> std::io::Error {
+        std::io::Error::new(err.kind(), err)
+    }
+}
+
+/// Best-effort conversion from a std::io::Error. Errors that were converted from an Error are
+/// recovered exactly, OS errors are translated with uv_translate_sys_error(), and anything else is
+/// mapped based on its ErrorKind, falling back to UNKNOWN.
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        if let Some(e) = err.get_ref().and_then(|e| e.downcast_ref::<Error>()) {
+            return *e;
+        }
+        if let Some(errno) = err.raw_os_error() {
+            return Error::from_inner(unsafe { uv_translate_sys_error(errno) } as uv::uv_errno_t);
+        }
+        match err.kind() {
+            ErrorKind::NotFound => Error::ENOENT,
+            ErrorKind::PermissionDenied => Error::EACCES,
+            ErrorKind::ConnectionRefused => Error::ECONNREFUSED,
+            ErrorKind::ConnectionReset => Error::ECONNRESET,
+            ErrorKind::ConnectionAborted => Error::ECONNABORTED,
+            ErrorKind::NotConnected => Error::ENOTCONN,
+            ErrorKind::AddrInUse => Error::EADDRINUSE,
+            ErrorKind::AddrNotAvailable => Error::EADDRNOTAVAIL,
+            ErrorKind::BrokenPipe => Error::EPIPE,
+            ErrorKind::AlreadyExists => Error::EEXIST,
+            ErrorKind::WouldBlock => Error::EAGAIN,
+            ErrorKind::InvalidInput => Error::EINVAL,
+            ErrorKind::TimedOut => Error::ETIMEDOUT,
+            ErrorKind::Interrupted => Error::EINTR,
+            ErrorKind::UnexpectedEof => Error::EOF,
+            ErrorKind::OutOfMemory => Error::ENOMEM,
+            ErrorKind::Unsupported => Error::ENOTSUP,
+            _ => Error::UNKNOWN,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct ConversionError {
     from: crate::HandleType,
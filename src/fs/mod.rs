@@ -92,6 +92,8 @@ impl crate::Loop {
             crate::uvret(unsafe { uv_fs_close(self.into_inner(), req.inner(), file as _, uv_cb) });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -131,6 +133,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -189,11 +193,16 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
 
-    /// Equivalent to preadv(2).
+    /// Equivalent to preadv(2). If offset is negative, the current file position is used and
+    /// updated. The number of bytes read is available from req.result() in the callback.
+    ///
+    /// The memory backing bufs is not copied, so it must remain valid until the callback runs.
     ///
     /// Warning: On Windows, under non-MSVC environments (e.g. when GCC or Clang is used to build
     /// libuv), files opened using the Filemap flag may cause a fatal crash if the memory mapped
@@ -230,6 +239,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -270,11 +281,16 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
 
-    /// Equivalent to pwritev(2).
+    /// Equivalent to pwritev(2). If offset is negative, the current file position is used and
+    /// updated. The number of bytes written is available from req.result() in the callback.
+    ///
+    /// The memory backing bufs is not copied, so it must remain valid until the callback runs.
     ///
     /// Warning: On Windows, under non-MSVC environments (e.g. when GCC or Clang is used to build
     /// libuv), files opened using the Filemap flag may cause a fatal crash if the memory mapped
@@ -327,6 +343,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -362,7 +380,9 @@ impl crate::Loop {
         })
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
-            req.destroy()
+            req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -393,6 +413,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -420,6 +442,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -447,6 +471,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -485,6 +511,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -512,6 +540,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -574,13 +604,16 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
 
     /// Start scanning a directory. Unlike most other fs_* methods, the callback is passed a
     /// ScandirIter which is an iterator over the entries in the directory. If you need access to
-    /// the FsReq in the callback, you can access iter.req.
+    /// the FsReq in the callback, you can access iter.req. The request is destroyed after the
+    /// callback returns, so the iterator must not be kept beyond the callback.
     ///
     /// Note: Unlike scandir(3), this function does not return the “.” and “..” entries.
     ///
@@ -592,7 +625,13 @@ impl crate::Loop {
         flags: FsOpenFlags,
         mut cb: impl FnMut(ScandirIter) + 'static,
     ) -> FsReqErrResult {
-        self._fs_scandir(path, flags, move |req| cb(ScandirIter { req }))
+        self._fs_scandir(path, flags, move |req| {
+            cb(ScandirIter {
+                req,
+                done: false,
+                owns_req: false,
+            })
+        })
     }
 
     /// Returns a ScandirIter that can be used to iterate over the contents of a directory.
@@ -601,8 +640,11 @@ impl crate::Loop {
         path: &str,
         flags: FsOpenFlags,
     ) -> Result<ScandirIter, Box<dyn std::error::Error>> {
-        self._fs_scandir(path, flags, ())
-            .map(|req| ScandirIter { req })
+        self._fs_scandir(path, flags, ()).map(|req| ScandirIter {
+            req,
+            done: false,
+            owns_req: true,
+        })
     }
 
     /// Private implementation for fs_stat()
@@ -617,6 +659,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -635,6 +679,30 @@ impl crate::Loop {
         })
     }
 
+    /// Equivalent to stat(2), returning a future that resolves to the file's Stat. This is the
+    /// async counterpart to fs_stat().
+    ///
+    /// Dropping the future before it resolves does not cancel the request: libuv will still
+    /// complete it, but the result will be discarded.
+    ///
+    /// Note: the future must be polled on the thread that is running the loop, and the loop must
+    /// be run for the future to resolve.
+    #[cfg(feature = "futures")]
+    pub fn fs_stat_async(&self, path: &str) -> crate::StatFuture {
+        let (completer, future) = crate::futures::channel();
+        let mut completer = Some(completer);
+        let cb = move |req: FsReq| {
+            if let Some(completer) = completer.take() {
+                let stat = req.result().map(|_| req.stat());
+                completer.send(stat.map_err(|e| Box::new(e) as _));
+            }
+        };
+        match self._fs_stat(path, cb) {
+            Ok(_) => future,
+            Err(e) => crate::ReqFuture::ready(Err(e)),
+        }
+    }
+
     /// Private implementation for fs_fstat()
     fn _fs_fstat<CB: Into<crate::FsCB<'static>>>(&self, file: File, cb: CB) -> FsReqResult {
         let cb = cb.into();
@@ -644,6 +712,8 @@ impl crate::Loop {
             crate::uvret(unsafe { uv_fs_fstat(self.into_inner(), req.inner(), file as _, uv_cb) });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -674,6 +744,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -704,6 +776,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -752,6 +826,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -781,6 +857,8 @@ impl crate::Loop {
             crate::uvret(unsafe { uv_fs_fsync(self.into_inner(), req.inner(), file as _, uv_cb) });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -811,6 +889,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -841,6 +921,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -887,6 +969,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -966,6 +1050,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1017,6 +1103,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1060,6 +1148,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1101,6 +1191,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1145,6 +1237,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1195,6 +1289,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1246,6 +1342,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1292,6 +1390,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1341,6 +1441,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1375,6 +1477,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1461,6 +1565,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1509,6 +1615,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1559,6 +1667,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -1591,14 +1701,26 @@ impl crate::Loop {
 ///
 /// Note: On Linux, getting the type of an entry is only supported by some file systems (btrfs,
 /// ext2, ext3 and ext4 at the time of this writing), check the getdents(2) man page.
+///
+/// If the scan itself failed (for example, ENOENT), the iterator yields that error once and then
+/// ends. An empty directory yields no entries.
 pub struct ScandirIter {
     pub req: FsReq,
+    done: bool,
+
+    // fs_scandir_sync() hands us the request, whereas in fs_scandir() it is destroyed by uv_fs_cb
+    // after the callback returns.
+    owns_req: bool,
 }
 
 impl Iterator for ScandirIter {
     type Item = crate::Result<crate::Dirent>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
         let mut dirent: uv::uv_dirent_t = unsafe { std::mem::zeroed() };
         let result =
             crate::uvret(unsafe { uv_fs_scandir_next(self.req.inner(), &mut dirent as _) });
@@ -1606,8 +1728,14 @@ impl Iterator for ScandirIter {
             Ok(_) => Some(Ok(crate::Dirent::from_inner(
                 &dirent as *const uv::uv_dirent_t,
             ))),
-            Err(crate::Error::EOF) => None,
-            Err(e) => Some(Err(e)),
+            Err(crate::Error::EOF) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
         }
     }
 }
@@ -1616,6 +1744,8 @@ impl std::iter::FusedIterator for ScandirIter {}
 
 impl Drop for ScandirIter {
     fn drop(&mut self) {
-        self.req.destroy();
+        if self.owns_req {
+            self.req.destroy();
+        }
     }
 }
@@ -1,6 +1,13 @@
 use crate::{FromInner, IntoInner};
+use std::time::SystemTime;
 use uv::uv_stat_t;
 
+// File type bits of Stat::mode. libuv uses the same values on all platforms.
+const S_IFMT: u64 = 0o170000;
+const S_IFDIR: u64 = 0o040000;
+const S_IFREG: u64 = 0o100000;
+const S_IFLNK: u64 = 0o120000;
+
 /// Portable equivalent of struct stat.
 pub struct Stat {
     pub dev: u64,
@@ -21,6 +28,45 @@ pub struct Stat {
     pub birthtim: crate::TimeSpec,
 }
 
+impl Stat {
+    /// Returns true if this is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.mode & S_IFMT == S_IFDIR
+    }
+
+    /// Returns true if this is a regular file.
+    pub fn is_file(&self) -> bool {
+        self.mode & S_IFMT == S_IFREG
+    }
+
+    /// Returns true if this is a symbolic link. Note that fs_stat() follows symbolic links, so
+    /// this can only be true for the result of fs_lstat().
+    pub fn is_symlink(&self) -> bool {
+        self.mode & S_IFMT == S_IFLNK
+    }
+
+    /// The last access time as a SystemTime.
+    pub fn accessed(&self) -> SystemTime {
+        self.atim.to_system_time()
+    }
+
+    /// The last modification time as a SystemTime.
+    pub fn modified(&self) -> SystemTime {
+        self.mtim.to_system_time()
+    }
+
+    /// The last status change time as a SystemTime.
+    pub fn changed(&self) -> SystemTime {
+        self.ctim.to_system_time()
+    }
+
+    /// The creation time as a SystemTime. Not all platforms and filesystems support this; when it
+    /// isn't supported, it is typically the Unix epoch or the same as changed().
+    pub fn created(&self) -> SystemTime {
+        self.birthtim.to_system_time()
+    }
+}
+
 impl FromInner<*const uv_stat_t> for Stat {
     fn from_inner(stat: *const uv_stat_t) -> Stat {
         unsafe {
@@ -1,4 +1,5 @@
 use crate::FromInner;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uv::uv_timespec_t;
 
 /// Portable equivalent of struct timespec
@@ -15,3 +16,21 @@ impl FromInner<uv_timespec_t> for TimeSpec {
         }
     }
 }
+
+impl TimeSpec {
+    /// Convert to a SystemTime, treating the TimeSpec as an offset from the Unix epoch.
+    pub fn to_system_time(&self) -> SystemTime {
+        let nsec = Duration::from_nanos(self.nsec as _);
+        if self.sec >= 0 {
+            UNIX_EPOCH + Duration::from_secs(self.sec as _) + nsec
+        } else {
+            UNIX_EPOCH - Duration::from_secs(self.sec.unsigned_abs()) + nsec
+        }
+    }
+}
+
+impl From<&TimeSpec> for SystemTime {
+    fn from(ts: &TimeSpec) -> SystemTime {
+        ts.to_system_time()
+    }
+}
@@ -0,0 +1,104 @@
+//! Adapters for using libuv requests from async code. These are only available with the `futures`
+//! feature enabled.
+//!
+//! libuv is single-threaded, so the futures in this module are not Send: they must be polled on
+//! the same thread that is running the loop.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+struct Shared<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    completed: bool,
+}
+
+/// A future that resolves when a libuv request completes. The result is delivered from the
+/// request's callback.
+///
+/// Dropping a ReqFuture before it resolves does not cancel the underlying request: libuv still
+/// owns the request and will finish it, but the result will be discarded.
+pub struct ReqFuture<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+/// The sending half of a ReqFuture. This is moved into the request callback.
+pub(crate) struct Completer<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+/// Creates a connected Completer/ReqFuture pair.
+pub(crate) fn channel<T>() -> (Completer<T>, ReqFuture<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        value: None,
+        waker: None,
+        completed: false,
+    }));
+    (
+        Completer {
+            shared: shared.clone(),
+        },
+        ReqFuture { shared },
+    )
+}
+
+impl<T> Completer<T> {
+    /// Resolve the associated ReqFuture with the given value. If the future has already been
+    /// dropped, the value is simply dropped.
+    pub(crate) fn send(self, value: T) {
+        let waker = {
+            let mut shared = self.shared.borrow_mut();
+            shared.value = Some(value);
+            shared.completed = true;
+            shared.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> ReqFuture<T> {
+    /// Create a ReqFuture that is already resolved with the given value. This is used when a
+    /// request fails before it is submitted to libuv.
+    pub(crate) fn ready(value: T) -> ReqFuture<T> {
+        let (completer, future) = channel();
+        completer.send(value);
+        future
+    }
+}
+
+impl<T> Future for ReqFuture<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut shared = self.shared.borrow_mut();
+        if let Some(value) = shared.value.take() {
+            return Poll::Ready(value);
+        }
+        if shared.completed {
+            panic!("ReqFuture polled after completion");
+        }
+        shared.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Future returned by StreamTrait::write_async(). Resolves to the number of bytes written.
+pub type WriteFuture = ReqFuture<crate::Result<usize>>;
+
+/// Future returned by Loop::queue_work_async(). Resolves to the status of the work request.
+pub type WorkFuture = ReqFuture<crate::Result<u32>>;
+
+/// Future returned by Loop::resolve_async(). Resolves to the resolved addresses.
+pub type ResolveFuture = ReqFuture<Result<Vec<std::net::SocketAddr>, Box<dyn std::error::Error>>>;
+
+/// Future returned by TcpHandle::connect_async() and PipeHandle::connect_async(). Resolves once
+/// the connection has been established or has failed.
+pub type ConnectFuture = ReqFuture<Result<(), Box<dyn std::error::Error>>>;
+
+/// Future returned by Loop::fs_stat_async(). Resolves to the file's Stat.
+pub type StatFuture = ReqFuture<Result<crate::Stat, Box<dyn std::error::Error>>>;
@@ -104,6 +104,12 @@ impl crate::ToHandle for AsyncHandle {
     }
 }
 
+impl std::fmt::Debug for AsyncHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("AsyncHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for AsyncHandle {
     type Error = crate::ConversionError;
 
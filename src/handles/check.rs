@@ -53,6 +53,8 @@ impl CheckHandle {
     /// Start the handle with the given callback. This function always succeeds, except when cb is
     /// ().
     pub fn start<CB: Into<CheckCB<'static>>>(&mut self, cb: CB) -> crate::Result<()> {
+        debug_assert_loop_thread!(self);
+
         // uv_cb is either Some(uv_check_cb) or None
         let cb = cb.into();
         let uv_cb = use_c_callback!(uv_check_cb, cb);
@@ -98,6 +100,12 @@ impl crate::ToHandle for CheckHandle {
     }
 }
 
+impl std::fmt::Debug for CheckHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("CheckHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for CheckHandle {
     type Error = crate::ConversionError;
 
@@ -142,6 +142,8 @@ impl FsEventHandle {
         flags: FsEventFlags,
         cb: CB,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        debug_assert_loop_thread!(self);
+
         let path = CString::new(path)?;
 
         // uv_cb is either Some(fs_event_cb) or None
@@ -217,6 +219,12 @@ impl crate::ToHandle for FsEventHandle {
     }
 }
 
+impl std::fmt::Debug for FsEventHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("FsEventHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for FsEventHandle {
     type Error = crate::ConversionError;
 
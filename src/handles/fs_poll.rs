@@ -84,6 +84,8 @@ impl FsPollHandle {
         interval: u32,
         cb: CB,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        debug_assert_loop_thread!(self);
+
         let path = CString::new(path)?;
 
         // uv_cb is either Some(fs_poll_cb) or None
@@ -159,6 +161,12 @@ impl crate::ToHandle for FsPollHandle {
     }
 }
 
+impl std::fmt::Debug for FsPollHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("FsPollHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for FsPollHandle {
     type Error = crate::ConversionError;
 
@@ -57,6 +57,10 @@ callbacks! {
 /// Data that we need to track with the handle.
 pub(crate) struct HandleData<'a> {
     pub(crate) close_cb: CloseCB<'a>,
+
+    /// Set while Loop::run_adaptive() has unref'd the handle, so that it only re-refs the handles
+    /// it unref'd itself. The flag goes away with the rest of the data when the handle is closed.
+    pub(crate) background: bool,
     pub(crate) addl: super::AddlHandleData<'a>,
 }
 
@@ -80,6 +84,10 @@ pub(crate) extern "C" fn uv_close_cb(handle: *mut uv_handle_t) {
 }
 
 /// Handle is the base type for all libuv handle types.
+///
+/// Handles are bound to the thread that created their loop: they must only be started, read,
+/// written, or closed from that thread. In debug builds, start(), read_start() and write() will
+/// panic if called from any other thread. Use an AsyncHandle to wake the loop from another thread.
 #[derive(Clone, Copy)]
 pub struct Handle {
     handle: *mut uv_handle_t,
@@ -90,6 +98,7 @@ impl Handle {
     pub(crate) fn initialize_data(handle: *mut uv_handle_t, addl: super::AddlHandleData) {
         let data: Box<HandleData> = Box::new(HandleData {
             close_cb: ().into(),
+            background: false,
             addl,
         });
         let ptr = Box::into_raw(data);
@@ -107,6 +116,33 @@ impl Handle {
         std::mem::drop(unsafe { Box::from_raw(ptr) });
         unsafe { uv_handle_set_data(handle, std::ptr::null_mut()) };
     }
+
+    /// Formats the handle's type, address, and active/closing state. This is used to implement
+    /// Debug for all of the handle types.
+    pub(crate) fn fmt_debug(
+        &self,
+        name: &str,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.debug_struct(name)
+            .field("type", &self.get_type().name())
+            .field("address", &self.handle)
+            .field("active", &self.is_active())
+            .field("closing", &self.is_closing())
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for Handle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_debug("Handle", f)
+    }
+}
+
+impl std::fmt::Display for Handle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} handle at {:p}", self.get_type(), self.handle)
+    }
 }
 
 pub trait ToHandle {
@@ -134,7 +170,7 @@ impl Inner<*mut uv_handle_t> for Handle {
 }
 
 pub trait HandleTrait: ToHandle {
-    /// Returns non-zero if the handle is active, zero if it’s inactive. What “active” means
+    /// Returns true if the handle is active, false if it’s inactive. What “active” means
     /// depends on the type of handle:
     ///   * An AsyncHandle is always active and cannot be deactivated, except by closing it with
     ///     close().
@@ -150,7 +186,7 @@ pub trait HandleTrait: ToHandle {
         unsafe { uv_is_active(self.to_handle().inner()) != 0 }
     }
 
-    /// Returns non-zero if the handle is closing or closed, zero otherwise.
+    /// Returns true if the handle is closing or closed, false otherwise.
     ///
     /// Note: This function should only be used between the initialization of the handle and the
     /// arrival of the close callback.
@@ -168,6 +204,11 @@ pub trait HandleTrait: ToHandle {
     ///
     /// In-progress requests, like ConnectRequest or WriteRequest, are cancelled and have their
     /// callbacks called asynchronously with status=UV_ECANCELED.
+    ///
+    /// Handle types are lightweight, copyable wrappers around a pointer, so they cannot free
+    /// themselves when dropped: close() is the teardown path for every handle. Once close_cb has
+    /// returned, the memory for the handle and its data is freed, so neither this handle nor any
+    /// copy of it may be used again.
     fn close<CB: Into<CloseCB<'static>>>(&mut self, cb: CB) {
         let handle = self.to_handle().inner();
 
@@ -189,11 +230,15 @@ pub trait HandleTrait: ToHandle {
 
     /// Un-reference the given handle. References are idempotent, that is, if a handle is not
     /// referenced calling this function again will have no effect.
+    ///
+    /// Loop::run() keeps running as long as there are active, referenced handles. An unreferenced
+    /// handle keeps working while the loop runs, but does not keep the loop alive by itself. This
+    /// is useful for background timers that shouldn't prevent the process from exiting.
     fn unref(&mut self) {
         unsafe { uv_unref(self.to_handle().inner()) };
     }
 
-    /// Returns true if the handle referenced, zero otherwise.
+    /// Returns true if the handle is referenced, false otherwise.
     fn has_ref(&self) -> bool {
         unsafe { uv_has_ref(self.to_handle().inner()) != 0 }
     }
@@ -237,7 +282,9 @@ pub trait HandleTrait: ToHandle {
     /// closed, this function will return EBADF.
     ///
     /// Warning: Be very careful when using this function. libuv assumes it’s in control of the
-    /// file descriptor so any change to it may lead to malfunction.
+    /// file descriptor so any change to it may lead to malfunction. The descriptor is owned by the
+    /// handle: do not close it, and do not keep using it once the handle has been closed, since
+    /// the OS may reuse the number for an unrelated file.
     fn get_fileno(&self) -> crate::Result<crate::OsFile> {
         let mut v: uv::uv_os_fd_t = 0 as _;
         crate::uvret(unsafe { uv_fileno(self.to_handle().inner(), &mut v as _) })?;
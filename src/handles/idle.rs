@@ -60,6 +60,8 @@ impl IdleHandle {
     /// Start the handle with the given callback. This function always succeeds, except when cb is
     /// ().
     pub fn start<CB: Into<IdleCB<'static>>>(&mut self, cb: CB) -> crate::Result<()> {
+        debug_assert_loop_thread!(self);
+
         // uv_cb is either Some(uv_idle_cb) or None
         let cb = cb.into();
         let uv_cb = use_c_callback!(uv_idle_cb, cb);
@@ -105,6 +107,12 @@ impl crate::ToHandle for IdleHandle {
     }
 }
 
+impl std::fmt::Debug for IdleHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("IdleHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for IdleHandle {
     type Error = crate::ConversionError;
 
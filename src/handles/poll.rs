@@ -129,7 +129,9 @@ impl PollHandle {
     /// called reporting an error status, but this is not guaranteed.
     ///
     /// Note: Calling start() on a handle that is already active is fine. Doing so will update the
-    /// events mask that is being watched for.
+    /// events mask that is being watched for, and replace the callback. The new mask replaces the
+    /// old one rather than being merged with it, so to start watching for WRITABLE in addition to
+    /// READABLE, pass READABLE | WRITABLE.
     ///
     /// Note: Though DISCONNECT can be set, it is unsupported on AIX and as such will not be set on
     /// the events field in the callback.
@@ -138,6 +140,8 @@ impl PollHandle {
         events: PollEvents,
         cb: CB,
     ) -> crate::Result<()> {
+        debug_assert_loop_thread!(self);
+
         // uv_cb is either Some(poll_cb) or None
         let cb = cb.into();
         let uv_cb = use_c_callback!(uv_poll_cb, cb);
@@ -182,6 +186,12 @@ impl crate::ToHandle for PollHandle {
     }
 }
 
+impl std::fmt::Debug for PollHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("PollHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for PollHandle {
     type Error = crate::ConversionError;
 
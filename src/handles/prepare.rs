@@ -54,6 +54,8 @@ impl PrepareHandle {
     /// Start the handle with the given callback. This function always succeeds, except when cb is
     /// ().
     pub fn start<CB: Into<PrepareCB<'static>>>(&mut self, cb: CB) -> crate::Result<()> {
+        debug_assert_loop_thread!(self);
+
         // uv_cb is either Some(uv_prepare_cb) or None
         let cb = cb.into();
         let uv_cb = use_c_callback!(uv_prepare_cb, cb);
@@ -99,6 +101,12 @@ impl crate::ToHandle for PrepareHandle {
     }
 }
 
+impl std::fmt::Debug for PrepareHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("PrepareHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for PrepareHandle {
     type Error = crate::ConversionError;
 
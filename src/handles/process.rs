@@ -185,6 +185,59 @@ impl<'a> ProcessOptions<'a> {
             gid: 0,
         }
     }
+
+    /// Sets the callback that is called after the process exits.
+    pub fn exit_cb<CB: Into<ExitCB<'static>>>(mut self, exit_cb: CB) -> Self {
+        self.exit_cb = exit_cb.into();
+        self
+    }
+
+    /// Replaces the command line arguments. As with new(), args[0] should be the path to the
+    /// program; unlike new(), this does not change the program that is executed.
+    pub fn args(mut self, args: &'a [&'a str]) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Sets the environment of the subprocess. Each entry should be of the form "NAME=value".
+    pub fn env(mut self, env: &'a [&'a str]) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Sets the directory the subprocess should execute in.
+    pub fn cwd(mut self, cwd: &'a str) -> Self {
+        self.cwd = Some(cwd);
+        self
+    }
+
+    /// Adds the given flags to the flags that control how spawn() behaves.
+    pub fn flags(mut self, flags: ProcessFlags) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Sets the stdio containers for the subprocess.
+    pub fn stdio(mut self, stdio: &'a [StdioContainer]) -> Self {
+        self.stdio = stdio;
+        self
+    }
+
+    /// Sets the user id of the subprocess, and the SETUID flag so that it is used. This is not
+    /// supported on windows; spawn() will fail with ENOTSUP.
+    pub fn uid(mut self, uid: crate::Uid) -> Self {
+        self.uid = uid;
+        self.flags |= ProcessFlags::SETUID;
+        self
+    }
+
+    /// Sets the group id of the subprocess, and the SETGID flag so that it is used. This is not
+    /// supported on windows; spawn() will fail with ENOTSUP.
+    pub fn gid(mut self, gid: crate::Gid) -> Self {
+        self.gid = gid;
+        self.flags |= ProcessFlags::SETGID;
+        self
+    }
 }
 
 /// Process handles will spawn a new process and allow the user to control it and establish
@@ -368,6 +421,12 @@ impl crate::ToHandle for ProcessHandle {
     }
 }
 
+impl std::fmt::Debug for ProcessHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("ProcessHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for ProcessHandle {
     type Error = crate::ConversionError;
 
@@ -393,4 +452,14 @@ impl crate::Loop {
         process.spawn(self, options)?;
         Ok(process)
     }
+
+    /// Create a new process handle and spawn the process, calling exit_cb when it exits. Any
+    /// exit_cb that was already set on the options is replaced.
+    pub fn spawn<CB: Into<ExitCB<'static>>>(
+        &self,
+        options: ProcessOptions,
+        exit_cb: CB,
+    ) -> Result<ProcessHandle, Box<dyn std::error::Error>> {
+        self.spawn_process(options.exit_cb(exit_cb))
+    }
 }
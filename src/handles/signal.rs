@@ -4,12 +4,14 @@ use uv::{uv_signal_init, uv_signal_start, uv_signal_start_oneshot, uv_signal_sto
 
 callbacks! {
     pub SignalCB(handle: SignalHandle, signum: i32);
+    pub SignalUntilCB(handle: SignalHandle, signum: i32) -> bool;
 }
 
 /// Additional data stored on the handle
 #[derive(Default)]
 pub(crate) struct SignalDataFields<'a> {
     signal_cb: SignalCB<'a>,
+    until_cb: SignalUntilCB<'a>,
 }
 
 /// Callback for uv_signal_start
@@ -24,6 +26,20 @@ extern "C" fn uv_signal_cb(handle: *mut uv_signal_t, signum: std::os::raw::c_int
     }
 }
 
+/// Callback for start_until
+extern "C" fn uv_signal_until_cb(handle: *mut uv_signal_t, signum: std::os::raw::c_int) {
+    let dataptr = crate::Handle::get_data(uv_handle!(handle));
+    if !dataptr.is_null() {
+        unsafe {
+            if let super::SignalData(d) = &mut (*dataptr).addl {
+                if !d.until_cb.call(handle.into_inner(), signum as _) {
+                    uv_signal_stop(handle);
+                }
+            }
+        }
+    }
+}
+
 /// Signal handles implement Unix style signal handling on a per-event loop bases.
 ///
 /// Windows notes: Reception of some signals is emulated:
@@ -78,6 +94,8 @@ impl SignalHandle {
 
     /// Start the handle with the given callback, watching for the given signal.
     pub fn start<CB: Into<SignalCB<'static>>>(&mut self, cb: CB, signum: i32) -> crate::Result<()> {
+        debug_assert_loop_thread!(self);
+
         // uv_cb is either Some(uv_signal_cb) or None
         let cb = cb.into();
         let uv_cb = use_c_callback!(uv_signal_cb, cb);
@@ -100,6 +118,8 @@ impl SignalHandle {
         cb: CB,
         signum: i32,
     ) -> crate::Result<()> {
+        debug_assert_loop_thread!(self);
+
         // uv_cb is either Some(uv_signal_cb) or None
         let cb = cb.into();
         let uv_cb = use_c_callback!(uv_signal_cb, cb);
@@ -115,6 +135,41 @@ impl SignalHandle {
         crate::uvret(unsafe { uv_signal_start_oneshot(self.handle, uv_cb, signum as _) })
     }
 
+    /// Like start(), but the callback decides whether to keep watching for the signal: if it
+    /// returns true, the handle keeps watching; if it returns false, the handle is stopped.
+    ///
+    /// This is an alternative to calling start_oneshot() again from its callback, which does not
+    /// work: libuv stops a oneshot handle after its callback returns, undoing any re-arm done by
+    /// the callback. A oneshot handler is also reset to the default disposition as soon as the
+    /// signal is delivered, so a second signal arriving before the handler is re-armed would be
+    /// handled by the default disposition (which, for many signals, terminates the process).
+    ///
+    /// Instead, the handler stays installed until the callback returns false, so a signal that
+    /// arrives while the callback is running is delivered to the callback once the current
+    /// invocation returns. As with start(), several signals arriving in quick succession may be
+    /// coalesced into one callback.
+    pub fn start_until<CB: Into<SignalUntilCB<'static>>>(
+        &mut self,
+        cb: CB,
+        signum: i32,
+    ) -> crate::Result<()> {
+        debug_assert_loop_thread!(self);
+
+        // uv_cb is either Some(uv_signal_until_cb) or None
+        let cb = cb.into();
+        let uv_cb = use_c_callback!(uv_signal_until_cb, cb);
+
+        // cb is either Some(closure) or None - it is saved into data
+        let dataptr = crate::Handle::get_data(uv_handle!(self.handle));
+        if !dataptr.is_null() {
+            if let super::SignalData(d) = unsafe { &mut (*dataptr).addl } {
+                d.until_cb = cb;
+            }
+        }
+
+        crate::uvret(unsafe { uv_signal_start(self.handle, uv_cb, signum as _) })
+    }
+
     /// Stop the handle, the callback will no longer be called.
     pub fn stop(&mut self) -> crate::Result<()> {
         crate::uvret(unsafe { uv_signal_stop(self.handle) })
@@ -150,6 +205,12 @@ impl crate::ToHandle for SignalHandle {
     }
 }
 
+impl std::fmt::Debug for SignalHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("SignalHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for SignalHandle {
     type Error = crate::ConversionError;
 
@@ -0,0 +1,131 @@
+use crate::StreamHandle;
+
+callbacks! {
+    pub FrameCB(stream: StreamHandle, frame: crate::Result<Vec<u8>>);
+}
+
+/// Byte order of a frame's length header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameEndianness {
+    Big,
+    Little,
+}
+
+/// Reads length-prefixed frames from a stream. Each frame consists of a fixed-size header, which
+/// is an unsigned integer giving the length of the body (not including the header itself),
+/// followed by the body. Frames may be split across any number of reads, and a single read may
+/// contain any number of frames: the FrameReader accumulates data internally and only delivers
+/// complete frames.
+///
+/// A FrameReader can be driven by a stream with start(), or fed data manually with feed().
+pub struct FrameReader {
+    header_len: usize,
+    endianness: FrameEndianness,
+    max_frame_size: usize,
+    buf: Vec<u8>,
+}
+
+impl FrameReader {
+    /// Create a new FrameReader. header_len is the size of the length header in bytes, from 1 to
+    /// 8; anything else returns EINVAL. Frames with a body larger than max_frame_size bytes are
+    /// rejected with EMSGSIZE.
+    pub fn new(
+        header_len: usize,
+        endianness: FrameEndianness,
+        max_frame_size: usize,
+    ) -> crate::Result<FrameReader> {
+        if !(1..=8).contains(&header_len) {
+            return Err(crate::Error::EINVAL);
+        }
+        Ok(FrameReader {
+            header_len,
+            endianness,
+            max_frame_size,
+            buf: Vec::new(),
+        })
+    }
+
+    /// Returns the number of bytes that have been received but are not yet part of a complete
+    /// frame.
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Decodes the length header at the start of header.
+    fn decode_len(&self, header: &[u8]) -> u64 {
+        let header = &header[..self.header_len];
+        match self.endianness {
+            FrameEndianness::Big => header.iter().fold(0, |len, &b| (len << 8) | b as u64),
+            FrameEndianness::Little => header.iter().rev().fold(0, |len, &b| (len << 8) | b as u64),
+        }
+    }
+
+    /// Add data to the internal buffer, calling f with the body of every frame that is now
+    /// complete, in order. If a frame's header specifies a body larger than max_frame_size (or too
+    /// large to address), EMSGSIZE is returned and the internal buffer is cleared: the stream can
+    /// no longer be parsed after that, so the caller should close it.
+    pub fn feed<F: FnMut(Vec<u8>)>(&mut self, data: &[u8], mut f: F) -> crate::Result<()> {
+        self.buf.extend_from_slice(data);
+
+        let mut pos = 0;
+        while self.buf.len() - pos >= self.header_len {
+            let len = self.decode_len(&self.buf[pos..]);
+            if len > self.max_frame_size as u64 {
+                self.buf.clear();
+                return Err(crate::Error::EMSGSIZE);
+            }
+
+            // len fits in a usize since it is no larger than max_frame_size, but the end of the
+            // frame may not if max_frame_size is huge
+            let start = pos + self.header_len;
+            let end = match start.checked_add(len as usize) {
+                Some(end) => end,
+                None => {
+                    self.buf.clear();
+                    return Err(crate::Error::EMSGSIZE);
+                }
+            };
+            if self.buf.len() < end {
+                break;
+            }
+            f(self.buf[start..end].to_vec());
+            pos = end;
+        }
+        self.buf.drain(..pos);
+        Ok(())
+    }
+
+    /// Start reading frames from the stream, calling cb with each complete frame. The FrameReader
+    /// is moved into the stream's read callback, replacing any previous one.
+    ///
+    /// If a read fails (including EOF), or a frame exceeds the maximum size (EMSGSIZE), reading is
+    /// stopped and cb is called one last time with the error. Any partial frame that was buffered
+    /// is discarded.
+    pub fn start<CB: Into<FrameCB<'static>>>(
+        mut self,
+        stream: &mut impl crate::StreamTrait,
+        cb: CB,
+    ) -> crate::Result<()> {
+        let mut cb = cb.into();
+        stream.read_start(
+            |_: crate::Handle, suggested_size: usize| {
+                crate::Buf::with_capacity(suggested_size).ok()
+            },
+            move |mut stream: StreamHandle,
+                  nread: crate::Result<usize>,
+                  mut buf: crate::ReadonlyBuf| {
+                let result = match nread {
+                    Ok(0) => Ok(()),
+                    Ok(len) => self.feed(&buf[..len], |frame| cb.call(stream, Ok(frame))),
+                    Err(e) => Err(e),
+                };
+                buf.dealloc();
+
+                if let Err(e) = result {
+                    let _ = crate::StreamTrait::read_stop(&mut stream);
+                    cb.call(stream, Err(e));
+                }
+            },
+        )
+    }
+}
@@ -2,6 +2,9 @@ mod addl_stream_data;
 use addl_stream_data::AddlStreamData::*;
 use addl_stream_data::*;
 
+pub mod frame_reader;
+pub use frame_reader::*;
+
 pub mod pipe;
 pub use pipe::*;
 
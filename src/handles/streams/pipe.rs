@@ -1,7 +1,6 @@
 use crate::{FromInner, HandleTrait, Inner, IntoInner, ToHandle};
 use std::convert::{TryFrom, TryInto};
 use std::ffi::CString;
-use std::net::SocketAddr;
 use uv::{
     uv_pipe_bind, uv_pipe_chmod, uv_pipe_connect, uv_pipe_getpeername, uv_pipe_getsockname,
     uv_pipe_init, uv_pipe_open, uv_pipe_pending_count, uv_pipe_pending_instances,
@@ -16,6 +15,9 @@ bitflags! {
     }
 }
 
+/// Alias of ChmodFlags, named after the handle type it applies to.
+pub type PipeChmodFlags = ChmodFlags;
+
 /// Pipe handles provide an abstraction over streaming files on Unix (including local domain
 /// sockets, pipes, and FIFOs) and named pipes on Windows.
 #[derive(Clone, Copy)]
@@ -55,7 +57,9 @@ impl PipeHandle {
         crate::uvret(unsafe { uv_pipe_open(self.handle, file) })
     }
 
-    /// Bind the pipe to a file path (Unix) or a name (Windows).
+    /// Bind the pipe to a file path (Unix) or a name (Windows). On Windows, the name must be of
+    /// the form `\\.\pipe\name`. On Unix, it is a filesystem path, and the socket file is created
+    /// by bind().
     ///
     /// Note: Paths on Unix get truncated to sizeof(sockaddr_un.sun_path) bytes, typically between
     /// 92 and 108 bytes.
@@ -65,7 +69,9 @@ impl PipeHandle {
             .map_err(|e| Box::new(e) as _)
     }
 
-    /// Connect to the Unix domain socket or the named pipe.
+    /// Connect to the Unix domain socket or the named pipe. See bind() for the format of name on
+    /// each platform. Errors connecting, such as ENOENT if nothing is bound to name, are reported
+    /// to the callback.
     ///
     /// Note: Paths on Unix get truncated to sizeof(sockaddr_un.sun_path) bytes, typically between
     /// 92 and 108 bytes.
@@ -74,8 +80,8 @@ impl PipeHandle {
         name: &str,
         cb: CB,
     ) -> Result<crate::ConnectReq, Box<dyn std::error::Error>> {
-        let req = crate::ConnectReq::new(cb)?;
         let name = CString::new(name)?;
+        let mut req = crate::ConnectReq::new(cb)?;
         unsafe {
             uv_pipe_connect(
                 req.inner(),
@@ -84,39 +90,105 @@ impl PipeHandle {
                 Some(crate::uv_connect_cb as _),
             )
         };
+
+        // uv_pipe_connect() reports errors through the callback, so the request is always
+        // submitted
+        req.set_submitted();
         Ok(req)
     }
 
+    /// Connect to the Unix domain socket or the named pipe, returning a future that resolves once
+    /// the connection has been established or has failed. This is the async counterpart to
+    /// connect().
+    ///
+    /// Dropping the future before it resolves does not cancel the connection attempt: libuv will
+    /// still complete it, but the result will be discarded.
+    ///
+    /// Note: the future must be polled on the thread that is running the loop, and the loop must
+    /// be run for the future to resolve.
+    #[cfg(feature = "futures")]
+    pub fn connect_async(&mut self, name: &str) -> crate::ConnectFuture {
+        let (completer, future) = crate::futures::channel();
+        let mut completer = Some(completer);
+        let cb = move |_: crate::ConnectReq, status: crate::Result<u32>| {
+            if let Some(completer) = completer.take() {
+                completer.send(status.map(|_| ()).map_err(|e| Box::new(e) as _));
+            }
+        };
+        match self.connect(name, cb) {
+            Ok(_) => future,
+            Err(e) => crate::ReqFuture::ready(Err(e)),
+        }
+    }
+
+    /// Calls uv_pipe_getsockname() or uv_pipe_getpeername(), growing the buffer as needed.
+    fn get_name(&self, peer: bool) -> crate::Result<Vec<u8>> {
+        let mut size = 256usize;
+        loop {
+            let mut buf: Vec<u8> = vec![0; size];
+            let mut len = size;
+            let result = crate::uvret(unsafe {
+                if peer {
+                    uv_pipe_getpeername(self.handle, buf.as_mut_ptr() as _, &mut len as *mut _ as _)
+                } else {
+                    uv_pipe_getsockname(self.handle, buf.as_mut_ptr() as _, &mut len as *mut _ as _)
+                }
+            });
+            match result {
+                Ok(_) => {
+                    // len is the length of the name, *not* including the null terminator
+                    buf.truncate(len);
+                    return Ok(buf);
+                }
+                // len has been set to the required size
+                Err(crate::Error::ENOBUFS) if len > size => size = len,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     /// Get the name of the Unix domain socket or the named pipe.
-    pub fn getsockname(&self) -> Result<SocketAddr, Box<dyn std::error::Error>> {
-        let mut sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
-        let mut sockaddr_len: std::os::raw::c_int =
-            std::mem::size_of::<uv::sockaddr_storage>() as _;
-        crate::uvret(unsafe {
-            uv_pipe_getsockname(
-                self.handle,
-                uv_handle!(&mut sockaddr),
-                uv_handle!(&mut sockaddr_len),
-            )
-        })?;
+    ///
+    /// Names in the Linux abstract namespace start with a null byte, which is included in the
+    /// returned String. Abstract names are not required to be valid UTF-8, so use
+    /// getsockname_bytes() if you need the exact name.
+    pub fn getsockname(&self) -> crate::Result<String> {
+        self.getsockname_bytes()
+            .map(|name| String::from_utf8_lossy(&name).into_owned())
+    }
 
-        crate::build_socketaddr(uv_handle!(&sockaddr))
+    /// Get the name of the Unix domain socket or the named pipe as raw bytes. Unlike
+    /// getsockname(), this returns names in the Linux abstract namespace (which start with a null
+    /// byte and may contain further null bytes) exactly.
+    pub fn getsockname_bytes(&self) -> crate::Result<Vec<u8>> {
+        self.get_name(false)
     }
 
     /// Get the name of the Unix domain socket or the named pipe to which the handle is connected.
-    pub fn getpeername(&self) -> Result<SocketAddr, Box<dyn std::error::Error>> {
-        let mut sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
-        let mut sockaddr_len: std::os::raw::c_int =
-            std::mem::size_of::<uv::sockaddr_storage>() as _;
-        crate::uvret(unsafe {
-            uv_pipe_getpeername(
-                self.handle,
-                uv_handle!(&mut sockaddr),
-                uv_handle!(&mut sockaddr_len),
-            )
-        })?;
+    ///
+    /// Names in the Linux abstract namespace start with a null byte, which is included in the
+    /// returned String. Abstract names are not required to be valid UTF-8, so use
+    /// getpeername_bytes() if you need the exact name.
+    pub fn getpeername(&self) -> crate::Result<String> {
+        self.getpeername_bytes()
+            .map(|name| String::from_utf8_lossy(&name).into_owned())
+    }
 
-        crate::build_socketaddr(uv_handle!(&sockaddr))
+    /// Get the name of the Unix domain socket or the named pipe to which the handle is connected,
+    /// as raw bytes. See getsockname_bytes().
+    pub fn getpeername_bytes(&self) -> crate::Result<Vec<u8>> {
+        self.get_name(true)
+    }
+
+    /// Returns true if the pipe is bound to a name in the Linux abstract socket namespace (that
+    /// is, a name starting with a null byte). Always returns false on other platforms, or if the
+    /// pipe is not bound.
+    pub fn is_abstract(&self) -> bool {
+        cfg!(target_os = "linux")
+            && self
+                .getsockname_bytes()
+                .map(|name| name.first() == Some(&0))
+                .unwrap_or(false)
     }
 
     /// Set the number of pending pipe instance handles when the pipe server is waiting for
@@ -127,6 +199,8 @@ impl PipeHandle {
         unsafe { uv_pipe_pending_instances(self.handle, count as _) };
     }
 
+    /// Returns the number of handles that have been received over this IPC pipe and are waiting
+    /// to be accepted. See pending_type().
     pub fn pending_count(&self) -> i32 {
         unsafe { uv_pipe_pending_count(self.handle) as _ }
     }
@@ -135,6 +209,11 @@ impl PipeHandle {
     ///
     /// First - call pending_count(), if it’s > 0 then initialize a handle of the given type,
     /// returned by pending_type() and call uv_accept(pipe, handle).
+    ///
+    /// Handles arrive along with data, so this is only meaningful after read_start() has been
+    /// called on the pipe: check pending_count() from within the read callback, and accept() each
+    /// pending handle (for example into a TcpHandle for HandleType::TCP, or a PipeHandle for
+    /// HandleType::NAMED_PIPE) before returning from the callback.
     pub fn pending_type(&self) -> crate::HandleType {
         unsafe { uv_pipe_pending_type(self.handle).into_inner() }
     }
@@ -142,6 +221,10 @@ impl PipeHandle {
     /// Alters pipe permissions, allowing it to be accessed from processes run by different users.
     /// Makes the pipe writable or readable by all users. Mode can be WRITABLE, READABLE or
     /// WRITABLE | READABLE. This function is blocking.
+    ///
+    /// Returns EINVAL if flags is empty, and EBADF if the pipe is not bound yet. If the platform
+    /// does not support changing the permissions of the pipe, the error from the operating system
+    /// is returned.
     pub fn chmod(&mut self, flags: ChmodFlags) -> crate::Result<()> {
         crate::uvret(unsafe { uv_pipe_chmod(self.handle, flags.bits()) })
     }
@@ -202,6 +285,12 @@ impl ToHandle for PipeHandle {
     }
 }
 
+impl std::fmt::Debug for PipeHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_handle().fmt_debug("PipeHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for PipeHandle {
     type Error = crate::ConversionError;
 
@@ -9,6 +9,7 @@ callbacks! {
     pub AllocCB(handle: crate::Handle, suggested_size: usize) -> Option<crate::Buf>;
     pub ConnectionCB(stream: StreamHandle, status: crate::Result<u32>);
     pub ReadCB(stream: StreamHandle, nread: crate::Result<usize>, buf: crate::ReadonlyBuf);
+    pub ReadVecCB(stream: StreamHandle, data: crate::Result<Vec<u8>>);
 }
 
 /// Additional data to store on the handle
@@ -16,6 +17,15 @@ pub(crate) struct StreamDataFields<'a> {
     pub(crate) alloc_cb: AllocCB<'a>,
     connection_cb: ConnectionCB<'a>,
     read_cb: ReadCB<'a>,
+    /// read callback that replaced read_cb while read_cb was running; it takes over once read_cb
+    /// returns, so that the running closure is not dropped out from under itself
+    pending_read_cb: Option<ReadCB<'a>>,
+    /// true while read_cb is running
+    in_read_cb: bool,
+    pub(crate) pending_writes: usize,
+    /// read buffer that read_start_alloc() reuses between reads; it is freed along with the rest
+    /// of the data when the handle is closed
+    read_buf: Option<Vec<u8>>,
     pub(crate) addl: super::AddlStreamData<'a>,
 }
 
@@ -33,7 +43,10 @@ pub(crate) extern "C" fn uv_alloc_cb(
                 .call(handle.into_inner(), suggested_size as _);
             match new_buf.as_mut() {
                 Some(new_buf) => {
+                    // libuv may fill the whole internal buffer, which also lets the ReadonlyBuf
+                    // in the read callback know the size of the allocation
                     buf.copy_from_nonoverlapping(new_buf.inner(), 1);
+                    (*buf).len = new_buf.capacity() as _;
                     new_buf.destroy_container();
                 }
                 None => {
@@ -70,9 +83,17 @@ extern "C" fn uv_read_cb(stream: *mut uv_stream_t, nread: NREAD, buf: *const uv:
             } else {
                 Ok(nread as usize)
             };
+
+            // the callback stays in place while it runs, so that resume() can find it; callbacks
+            // that replace it in the meantime are held in pending_read_cb until it returns
+            (*dataptr).in_read_cb = true;
             (*dataptr)
                 .read_cb
                 .call(stream.into_inner(), nread as _, buf.into_inner());
+            (*dataptr).in_read_cb = false;
+            if let Some(read_cb) = (*dataptr).pending_read_cb.take() {
+                (*dataptr).read_cb = read_cb;
+            }
         }
     }
 }
@@ -91,6 +112,10 @@ impl StreamHandle {
             alloc_cb: ().into(),
             connection_cb: ().into(),
             read_cb: ().into(),
+            pending_read_cb: None,
+            in_read_cb: false,
+            pending_writes: 0,
+            read_buf: None,
             addl,
         });
         crate::Handle::initialize_data(uv_handle!(stream), data);
@@ -104,6 +129,94 @@ impl StreamHandle {
         }
         std::ptr::null_mut()
     }
+
+    /// Replaces the read callback. If the read callback is running, the replacement takes effect
+    /// once it returns.
+    fn replace_read_cb<'a>(dataptr: *mut StreamDataFields<'a>, read_cb: ReadCB<'a>) {
+        unsafe {
+            if (*dataptr).in_read_cb {
+                (*dataptr).pending_read_cb = Some(read_cb);
+            } else {
+                (*dataptr).read_cb = read_cb;
+            }
+        }
+    }
+
+    /// Takes the buffer that read_start_alloc() keeps on the handle, if it is at least size bytes,
+    /// or allocates a new one.
+    fn take_read_buf(stream: *mut uv_stream_t, size: usize) -> Vec<u8> {
+        let dataptr = StreamHandle::get_data(stream);
+        if !dataptr.is_null() {
+            if let Some(buf) = unsafe { (*dataptr).read_buf.take() } {
+                if buf.capacity() >= size {
+                    return buf;
+                }
+            }
+        }
+        Vec::with_capacity(size)
+    }
+
+    /// Gives the internal buffer of a ReadonlyBuf that was allocated by take_read_buf() back to
+    /// the handle so the next read can reuse it. If the handle has no data, it is freed instead.
+    fn recycle_read_buf(stream: *mut uv_stream_t, buf: &mut crate::ReadonlyBuf) {
+        if !buf.is_allocated() {
+            return;
+        }
+
+        let dataptr = StreamHandle::get_data(stream);
+        if dataptr.is_null() {
+            buf.dealloc();
+            return;
+        }
+
+        // the read callback is given the full capacity of the buffer as its length, and the buffer
+        // was allocated by a Vec<u8>, so the Vec can take it back
+        let ptr: *const uv::uv_buf_t = buf.inner();
+        unsafe {
+            let capacity = (*ptr).len as _;
+            (*dataptr).read_buf = Some(Vec::from_raw_parts((*ptr).base as *mut u8, 0, capacity));
+        }
+    }
+
+    /// Submits a write request with uv_write (or uv_write2 if send_handle is given), destroying
+    /// the request if the submission fails. Successful submissions are counted in
+    /// pending_write_count().
+    pub(crate) fn submit_write(
+        &self,
+        mut req: crate::WriteReq,
+        send_handle: Option<&StreamHandle>,
+    ) -> crate::Result<crate::WriteReq> {
+        let result = crate::uvret(unsafe {
+            match send_handle {
+                Some(send_handle) => uv_write2(
+                    req.inner(),
+                    self.handle,
+                    req.bufs_ptr,
+                    req.bufs_len as _,
+                    send_handle.inner(),
+                    Some(crate::uv_write_cb),
+                ),
+                None => uv_write(
+                    req.inner(),
+                    self.handle,
+                    req.bufs_ptr,
+                    req.bufs_len as _,
+                    Some(crate::uv_write_cb),
+                ),
+            }
+        });
+        if let Err(e) = result {
+            req.destroy();
+            return Err(e);
+        }
+        req.set_submitted();
+
+        let dataptr = StreamHandle::get_data(self.handle);
+        if !dataptr.is_null() {
+            unsafe { (*dataptr).pending_writes += 1 };
+        }
+        Ok(req)
+    }
 }
 
 pub trait ToStream {
@@ -154,6 +267,12 @@ impl crate::ToHandle for StreamHandle {
     }
 }
 
+impl std::fmt::Debug for StreamHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("StreamHandle", f)
+    }
+}
+
 pub trait StreamTrait: ToStream {
     /// Shutdown the outgoing (write) side of a duplex stream. It waits for pending write requests
     /// to complete. The handle should refer to a initialized stream. The cb is called after
@@ -172,6 +291,8 @@ pub trait StreamTrait: ToStream {
         });
         if result.is_err() {
             req.destroy();
+        } else {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -217,6 +338,8 @@ pub trait StreamTrait: ToStream {
         alloc_cb: ACB,
         read_cb: RCB,
     ) -> crate::Result<()> {
+        debug_assert_loop_thread!(&self.to_stream());
+
         // uv_alloc_cb is either Some(alloc_cb) or None
         // uv_read_cb is either Some(read_cb) or None
         let alloc_cb = alloc_cb.into();
@@ -228,15 +351,65 @@ pub trait StreamTrait: ToStream {
         // read_cb is either Some(closure) or None
         let dataptr = StreamHandle::get_data(self.to_stream().inner());
         if !dataptr.is_null() {
-            unsafe {
-                (*dataptr).alloc_cb = alloc_cb;
-                (*dataptr).read_cb = read_cb;
-            }
+            unsafe { (*dataptr).alloc_cb = alloc_cb };
+            StreamHandle::replace_read_cb(dataptr, read_cb);
         }
 
         crate::uvret(unsafe { uv_read_start(self.to_stream().inner(), uv_alloc_cb, uv_read_cb) })
     }
 
+    /// Read data from an incoming stream, letting the crate manage buffer allocation. Each time
+    /// data is read, read_cb is given an owned Vec<u8> containing exactly the bytes that were read
+    /// (its length is already trimmed to nread), so there is nothing to free and no way to read
+    /// past the end of the data. When the other end closes its side of the stream, read_cb
+    /// receives Err(EOF); other read errors are passed along the same way. Reads of zero bytes
+    /// are not reported.
+    ///
+    /// Each stream keeps a single read buffer of the size suggested by libuv, which is reused for
+    /// every read and freed when the stream is closed. The bytes that were read are copied out of
+    /// it into a Vec of exactly nread bytes. Use read_start() instead if you need control over how
+    /// buffers are allocated.
+    fn read_start_alloc<CB: Into<ReadVecCB<'static>>>(&mut self, read_cb: CB) -> crate::Result<()> {
+        let mut read_cb = read_cb.into();
+        self.read_start(
+            |handle: crate::Handle, suggested_size: usize| {
+                let stream: *mut uv::uv_handle_t = handle.inner();
+                let buf = StreamHandle::take_read_buf(stream as _, suggested_size);
+                Some(crate::Buf::from_vec(buf))
+            },
+            move |stream: StreamHandle,
+                  nread: crate::Result<usize>,
+                  mut buf: crate::ReadonlyBuf| {
+                let data = nread.map(|len| buf.as_slice(len).to_vec());
+
+                // hand the buffer back before calling read_cb, which may close the stream
+                StreamHandle::recycle_read_buf(stream.inner(), &mut buf);
+                match data {
+                    Ok(data) if data.is_empty() => (),
+                    data => read_cb.call(stream, data),
+                }
+            },
+        )
+    }
+
+    /// Replace the read callback that was passed to read_start() without stopping and restarting
+    /// reads, so no data is dropped. The alloc callback is unchanged. This is useful for protocols
+    /// that change how data is handled between phases, such as after a handshake. It may be
+    /// called from within the read callback itself, in which case the new callback is used
+    /// starting with the next read.
+    ///
+    /// This is only meaningful while reading is active (after read_start() and before
+    /// read_stop()): it does not start reading. Returns EINVAL if read_cb is empty.
+    fn set_read_callback<CB: Into<ReadCB<'static>>>(&mut self, read_cb: CB) -> crate::Result<()> {
+        let read_cb = read_cb.into();
+        let dataptr = StreamHandle::get_data(self.to_stream().inner());
+        if dataptr.is_null() || read_cb.is_nil() {
+            return Err(crate::Error::EINVAL);
+        }
+        StreamHandle::replace_read_cb(dataptr, read_cb);
+        Ok(())
+    }
+
     /// Stop reading data from the stream. The uv_read_cb callback will no longer be called.
     ///
     /// This function is idempotent and may be safely called on a stopped stream.
@@ -244,8 +417,47 @@ pub trait StreamTrait: ToStream {
         crate::uvret(unsafe { uv_read_stop(self.to_stream().inner()) })
     }
 
+    /// Temporarily stop reading from the stream. The callbacks passed to read_start() are
+    /// remembered so that reading can be restarted with resume().
+    ///
+    /// Together with get_write_queue_size(), this can be used to implement backpressure: for
+    /// example, a proxy can pause() its source when the destination's write queue grows too large
+    /// and resume() it once the queue drains.
+    fn pause(&mut self) -> crate::Result<()> {
+        self.read_stop()
+    }
+
+    /// Restart reading from the stream using the callbacks that were last passed to read_start().
+    /// Returns EINVAL if read_start() was never called. Like pause(), this may be called from
+    /// within the read callback.
+    fn resume(&mut self) -> crate::Result<()> {
+        let dataptr = StreamHandle::get_data(self.to_stream().inner());
+        if dataptr.is_null() {
+            return Err(crate::Error::EINVAL);
+        }
+
+        let (uv_alloc_cb, uv_read_cb) = unsafe {
+            (
+                use_c_callback!(uv_alloc_cb, (*dataptr).alloc_cb),
+                use_c_callback!(uv_read_cb, (*dataptr).read_cb),
+            )
+        };
+        if uv_alloc_cb.is_none() || uv_read_cb.is_none() {
+            return Err(crate::Error::EINVAL);
+        }
+
+        crate::uvret(unsafe { uv_read_start(self.to_stream().inner(), uv_alloc_cb, uv_read_cb) })
+    }
+
     /// Write data to stream. Buffers are written in order.
     ///
+    /// A zero-length write (either an empty slice of buffers, or buffers that are all empty) is
+    /// allowed and can be used as a sync point: it is queued behind any pending writes, and its
+    /// callback is called once all of the writes queued before it have completed. It is still
+    /// passed to the operating system as a write of zero bytes, so no data reaches the other end,
+    /// but it can fail like any other write: for example with EPIPE if the other end has been
+    /// closed.
+    ///
     /// Note: The memory pointed to by the buffers must remain valid until the callback gets
     /// called.
     fn write<CB: Into<crate::WriteCB<'static>>>(
@@ -253,20 +465,118 @@ pub trait StreamTrait: ToStream {
         bufs: &[impl crate::BufTrait],
         cb: CB,
     ) -> crate::Result<crate::WriteReq> {
-        let mut req = crate::WriteReq::new(bufs, cb)?;
-        let result = crate::uvret(unsafe {
-            uv_write(
-                req.inner(),
-                self.to_stream().inner(),
-                req.bufs_ptr,
-                bufs.len() as _,
-                Some(crate::uv_write_cb),
-            )
-        });
-        if result.is_err() {
-            req.destroy();
+        debug_assert_loop_thread!(&self.to_stream());
+
+        let req = crate::WriteReq::new(bufs, cb)?;
+        self.to_stream().submit_write(req, None)
+    }
+
+    /// Write several owned buffers to stream in a single write request. Buffers are written in
+    /// order. Unlike write(), the buffers are owned by the request, so there are no lifetime
+    /// requirements on the caller: the buffers are dropped after the callback is called.
+    fn write_owned_vectored<CB: Into<crate::WriteCB<'static>>>(
+        &mut self,
+        data: Vec<Vec<u8>>,
+        cb: CB,
+    ) -> crate::Result<crate::WriteReq> {
+        debug_assert_loop_thread!(&self.to_stream());
+
+        let bufs = data
+            .iter()
+            .map(|d| unsafe { uv::uv_buf_init(d.as_ptr() as _, d.len() as _) })
+            .collect();
+
+        // The closure owns the data, which is dropped when the request is destroyed after the
+        // callback is called.
+        let mut cb = cb.into();
+        let mut data = Some(data);
+        let cb = move |req: crate::WriteReq, status: crate::Result<u32>| {
+            cb.call(req, status);
+            data.take();
+        };
+
+        let req = crate::WriteReq::new_with_uv_bufs(bufs, cb)?;
+        self.to_stream().submit_write(req, None)
+    }
+
+    /// Write the first len bytes of buf to stream, taking ownership of buf. The Buf is destroy()'d
+    /// (both the internal buffer and the Buf itself) after the callback is called, so the caller
+    /// must not use, reuse, or recycle it after calling this function - not even if the write
+    /// fails, in which case it is destroyed immediately. If len is larger than the buffer, the
+    /// whole buffer is written.
+    ///
+    /// Combined with ReadonlyBuf::into_buf(), this allows data to be proxied from one stream to
+    /// another without copying: in the read callback of the source, hand the buffer that was
+    /// allocated by the allocate callback straight to write_buf() on the destination, passing the
+    /// number of bytes read as len.
+    fn write_buf<CB: Into<crate::WriteCB<'static>>>(
+        &mut self,
+        buf: crate::Buf,
+        len: usize,
+        cb: CB,
+    ) -> crate::Result<crate::WriteReq> {
+        debug_assert_loop_thread!(&self.to_stream());
+
+        let mut uv_buf = unsafe { *Inner::<*mut uv::uv_buf_t>::inner(&buf) };
+        if (uv_buf.len as usize) > len {
+            uv_buf.len = len as _;
+        }
+
+        // Destroys the Buf when dropped
+        struct OwnedBuf(crate::Buf);
+        impl Drop for OwnedBuf {
+            fn drop(&mut self) {
+                self.0.destroy();
+            }
+        }
+
+        // The closure owns the Buf, which is destroyed along with the request after the callback
+        // is called (or immediately, if the write fails). The uv_buf_t written by libuv is a copy
+        // with a (possibly) smaller len, so the Buf still knows the size of its allocation.
+        let mut cb = cb.into();
+        let buf = OwnedBuf(buf);
+        let cb = move |req: crate::WriteReq, status: crate::Result<u32>| {
+            let _ = &buf;
+            cb.call(req, status);
+        };
+
+        let req = crate::WriteReq::new_with_uv_bufs(vec![uv_buf], cb)?;
+        self.to_stream().submit_write(req, None)
+    }
+
+    /// Write data to stream, returning a future that resolves to the number of bytes written. This
+    /// is the async counterpart to write(). The data is owned by the request until the write
+    /// completes, so there are no lifetime requirements on the caller.
+    ///
+    /// Dropping the future before it resolves does not cancel the write: libuv will still complete
+    /// it, but the result will be discarded.
+    ///
+    /// Note: the future must be polled on the thread that is running the loop.
+    #[cfg(feature = "futures")]
+    fn write_async(&mut self, data: Vec<u8>) -> crate::WriteFuture {
+        debug_assert_loop_thread!(&self.to_stream());
+
+        let (completer, future) = crate::futures::channel();
+        let len = data.len();
+        let bufs = vec![unsafe { uv::uv_buf_init(data.as_ptr() as _, len as _) }];
+
+        // The closure owns both the data and the completer. The data is dropped when the request
+        // is destroyed, which happens after the callback is called.
+        let mut data = Some(data);
+        let mut completer = Some(completer);
+        let cb = move |_: crate::WriteReq, status: crate::Result<u32>| {
+            data.take();
+            if let Some(completer) = completer.take() {
+                completer.send(status.map(|_| len));
+            }
+        };
+
+        let result = crate::WriteReq::new_with_uv_bufs(bufs, cb)
+            .and_then(|req| self.to_stream().submit_write(req, None));
+        match result {
+            Ok(_) => future,
+            Err(e) => crate::ReqFuture::ready(Err(e)),
         }
-        result.map(|_| req)
     }
 
     /// Extended write function for sending handles over a pipe. The pipe must be initialized with
@@ -283,29 +593,26 @@ pub trait StreamTrait: ToStream {
         bufs: &[impl crate::BufTrait],
         cb: CB,
     ) -> crate::Result<crate::WriteReq> {
-        let mut req = crate::WriteReq::new(bufs, cb)?;
-        let result = crate::uvret(unsafe {
-            uv_write2(
-                req.inner(),
-                self.to_stream().inner(),
-                req.bufs_ptr,
-                bufs.len() as _,
-                send_handle.inner(),
-                Some(crate::uv_write_cb),
-            )
-        });
-        if result.is_err() {
-            req.destroy();
-        }
-        result.map(|_| req)
+        debug_assert_loop_thread!(&self.to_stream());
+
+        let req = crate::WriteReq::new(bufs, cb)?;
+        self.to_stream().submit_write(req, Some(send_handle))
     }
 
     /// Same as write(), but won’t queue a write request if it can’t be completed immediately.
     ///
-    /// Will return number of bytes written (can be less than the supplied buffer size).
+    /// Will return number of bytes written (can be less than the supplied buffer size). If bufs is
+    /// empty, or all of the buffers are empty, nothing is written and Ok(0) is returned.
     fn try_write(&mut self, bufs: &[impl crate::BufTrait]) -> crate::Result<i32> {
         let (bufs_ptr, bufs_len, bufs_capacity) = bufs.into_inner();
-        let result = unsafe { uv_try_write(self.to_stream().inner(), bufs_ptr, bufs_len as _) };
+        let is_empty = unsafe { std::slice::from_raw_parts(bufs_ptr, bufs_len) }
+            .iter()
+            .all(|buf| buf.len == 0);
+        let result = if is_empty {
+            0
+        } else {
+            unsafe { uv_try_write(self.to_stream().inner(), bufs_ptr, bufs_len as _) }
+        };
 
         unsafe { std::mem::drop(Vec::from_raw_parts(bufs_ptr, bufs_len, bufs_capacity)) };
 
@@ -347,6 +654,61 @@ pub trait StreamTrait: ToStream {
     fn get_write_queue_size(&self) -> usize {
         unsafe { uv_stream_get_write_queue_size(self.to_stream().inner()) as _ }
     }
+
+    /// Returns the number of write requests that have been submitted but whose callbacks have not
+    /// been called yet. Unlike get_write_queue_size(), which counts bytes, this counts requests,
+    /// which is useful to cap the number of outstanding writes regardless of their size.
+    ///
+    /// Note: only writes submitted through this crate's write methods (write(), write2(), etc) are
+    /// counted.
+    fn pending_write_count(&self) -> usize {
+        let dataptr = StreamHandle::get_data(self.to_stream().inner());
+        if dataptr.is_null() {
+            0
+        } else {
+            unsafe { (*dataptr).pending_writes }
+        }
+    }
+
+    /// Start reading from the stream and write everything that is read straight back to it. When
+    /// the other end closes its side of the stream (EOF), the stream is shutdown() and then
+    /// closed. On any other read error, or if a write cannot be submitted, the stream is closed
+    /// immediately.
+    ///
+    /// Buffers are handed from the read callback to write_buf(), so data is never copied. This is
+    /// primarily intended for tests, examples, and quick-and-dirty echo servers: call it on each
+    /// client accepted in a listen() callback.
+    fn echo(&mut self) -> crate::Result<()> {
+        self.read_start(
+            |_: crate::Handle, suggested_size: usize| {
+                crate::Buf::with_capacity(suggested_size).ok()
+            },
+            |mut stream: StreamHandle, nread: crate::Result<usize>, mut buf: crate::ReadonlyBuf| {
+                match nread {
+                    Ok(len) if len > 0 => {
+                        if stream.write_buf(buf.into_buf(), len, ()).is_err() {
+                            crate::HandleTrait::close(&mut stream, ());
+                        }
+                    }
+                    Ok(_) => buf.dealloc(),
+                    Err(crate::Error::EOF) => {
+                        buf.dealloc();
+                        let result =
+                            stream.shutdown(|req: crate::ShutdownReq, _: crate::Result<u32>| {
+                                crate::HandleTrait::close(&mut req.handle(), ());
+                            });
+                        if result.is_err() {
+                            crate::HandleTrait::close(&mut stream, ());
+                        }
+                    }
+                    Err(_) => {
+                        buf.dealloc();
+                        crate::HandleTrait::close(&mut stream, ());
+                    }
+                }
+            },
+        )
+    }
 }
 
 impl StreamTrait for StreamHandle {}
@@ -3,8 +3,8 @@ use std::convert::{TryFrom, TryInto};
 use std::net::SocketAddr;
 use uv::{
     uv_tcp_bind, uv_tcp_close_reset, uv_tcp_connect, uv_tcp_getpeername, uv_tcp_getsockname,
-    uv_tcp_init, uv_tcp_init_ex, uv_tcp_keepalive, uv_tcp_nodelay, uv_tcp_simultaneous_accepts,
-    uv_tcp_t, AF_INET, AF_INET6, AF_UNSPEC,
+    uv_tcp_init, uv_tcp_init_ex, uv_tcp_keepalive, uv_tcp_nodelay, uv_tcp_open,
+    uv_tcp_simultaneous_accepts, uv_tcp_t, AF_INET, AF_INET6, AF_UNSPEC,
 };
 
 bitflags! {
@@ -70,18 +70,32 @@ impl TcpHandle {
         Ok(TcpHandle { handle })
     }
 
+    /// Open an existing file descriptor or SOCKET as a TCP handle, such as a listening socket
+    /// inherited through systemd socket activation. The handle takes over the socket: it is
+    /// closed when the handle is closed. After opening, use the handle as usual, for example by
+    /// calling listen() or read_start().
+    ///
+    /// Note: The passed file descriptor or SOCKET is not checked for its type, but it’s required
+    /// that it represents a valid stream socket.
+    pub fn open(&mut self, sock: crate::Socket) -> crate::Result<()> {
+        crate::uvret(unsafe { uv_tcp_open(self.handle, sock as _) })
+    }
+
     /// Enable TCP_NODELAY, which disables Nagle’s algorithm.
     pub fn nodelay(&mut self, enable: bool) -> crate::Result<()> {
         crate::uvret(unsafe { uv_tcp_nodelay(self.handle, if enable { 1 } else { 0 }) })
     }
 
     /// Enable / disable TCP keep-alive. delay is the initial delay in seconds, ignored when enable
-    /// is zero.
+    /// is false. delay must be non-zero when enable is true, otherwise EINVAL is returned.
     ///
     /// After delay has been reached, 10 successive probes, each spaced 1 second from the previous
     /// one, will still happen. If the connection is still lost at the end of this procedure, then
     /// the handle is destroyed with a ETIMEDOUT error passed to the corresponding callback.
     pub fn keepalive(&mut self, enable: bool, delay: u32) -> crate::Result<()> {
+        if enable && delay == 0 {
+            return Err(crate::Error::EINVAL);
+        }
         crate::uvret(unsafe { uv_tcp_keepalive(self.handle, if enable { 1 } else { 0 }, delay) })
     }
 
@@ -97,6 +111,86 @@ impl TcpHandle {
         })
     }
 
+    /// Set a socket option on the underlying socket with setsockopt(2).
+    #[cfg(unix)]
+    fn setsockopt<T>(&self, level: i32, name: i32, value: &T) -> crate::Result<()> {
+        let fd = self.get_fileno()?;
+        let ret = unsafe {
+            libc::setsockopt(
+                fd as _,
+                level,
+                name,
+                value as *const T as _,
+                std::mem::size_of::<T>() as _,
+            )
+        };
+        if ret < 0 {
+            Err(crate::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set the type-of-service field (IP_TOS, or IPV6_TCLASS for IPv6 sockets) of outgoing
+    /// packets. This is commonly used for DSCP marking: the DSCP value occupies the upper 6 bits,
+    /// so pass dscp << 2.
+    ///
+    /// The handle must have a socket, meaning it must have been created with new_ex() or bound,
+    /// connected, or opened. Otherwise, EBADF is returned.
+    ///
+    /// This is implemented with setsockopt(2) on the handle's file descriptor, because libuv does
+    /// not wrap this option. It is not implemented on Windows, where it returns ENOTSUP (Windows
+    /// ignores IP_TOS unless configured through group policy/QoS APIs, and its socket option
+    /// numbering differs).
+    #[cfg(unix)]
+    pub fn set_tos(&mut self, tos: u8) -> crate::Result<()> {
+        let fd = self.get_fileno()?;
+        let mut storage: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+        let mut len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        let ret = unsafe { libc::getsockname(fd as _, &mut storage as *mut _ as _, &mut len as _) };
+        if ret < 0 {
+            return Err(crate::Error::last_os_error());
+        }
+
+        let tos = tos as libc::c_int;
+        if storage.ss_family as libc::c_int == libc::AF_INET6 {
+            self.setsockopt(libc::IPPROTO_IPV6, libc::IPV6_TCLASS, &tos)
+        } else {
+            self.setsockopt(libc::IPPROTO_IP, libc::IP_TOS, &tos)
+        }
+    }
+
+    /// Set the type-of-service field of outgoing packets. Not implemented on Windows.
+    #[cfg(windows)]
+    pub fn set_tos(&mut self, _tos: u8) -> crate::Result<()> {
+        Err(crate::Error::ENOTSUP)
+    }
+
+    /// Set the SO_LINGER socket option. If linger is Some, closing the socket will block (for up
+    /// to the given duration, rounded down to whole seconds) while unsent data is transmitted. If
+    /// linger is None, lingering is disabled, which is the default. To reset a connection, use
+    /// close_reset() instead.
+    ///
+    /// The handle must have a socket, meaning it must have been created with new_ex() or bound,
+    /// connected, or opened. Otherwise, EBADF is returned.
+    ///
+    /// This is implemented with setsockopt(2) on the handle's file descriptor, because libuv does
+    /// not wrap this option. It is not implemented on Windows, where it returns ENOTSUP.
+    #[cfg(unix)]
+    pub fn set_linger(&mut self, linger: Option<std::time::Duration>) -> crate::Result<()> {
+        let value = libc::linger {
+            l_onoff: if linger.is_some() { 1 } else { 0 },
+            l_linger: linger.map(|d| d.as_secs() as _).unwrap_or(0),
+        };
+        self.setsockopt(libc::SOL_SOCKET, libc::SO_LINGER, &value)
+    }
+
+    /// Set the SO_LINGER socket option. Not implemented on Windows.
+    #[cfg(windows)]
+    pub fn set_linger(&mut self, _linger: Option<std::time::Duration>) -> crate::Result<()> {
+        Err(crate::Error::ENOTSUP)
+    }
+
     /// Bind the handle to an address and port.
     ///
     /// When the port is already taken, you can expect to see an EADDRINUSE error from either
@@ -110,13 +204,14 @@ impl TcpHandle {
         addr: &SocketAddr,
         flags: TcpBindFlags,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut sockaddr: uv::sockaddr = unsafe { std::mem::zeroed() };
-        crate::fill_sockaddr(&mut sockaddr, addr)?;
-        crate::uvret(unsafe { uv_tcp_bind(self.handle, &sockaddr as _, flags.bits()) })
+        let sockaddr = crate::to_sockaddr(addr)?;
+        crate::uvret(unsafe { uv_tcp_bind(self.handle, uv_handle!(&sockaddr), flags.bits()) })
             .map_err(|e| Box::new(e) as _)
     }
 
     /// Get the current address to which the handle is bound.
+    ///
+    /// Returns EINVAL if the handle has not been bound yet, regardless of platform.
     pub fn getsockname(&self) -> Result<SocketAddr, Box<dyn std::error::Error>> {
         let mut sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
         let mut sockaddr_len: std::os::raw::c_int =
@@ -129,7 +224,7 @@ impl TcpHandle {
             )
         })?;
 
-        crate::build_socketaddr(uv_handle!(&sockaddr))
+        crate::build_bound_socketaddr(uv_handle!(&sockaddr))
     }
 
     /// Get the address of the peer connected to the handle.
@@ -160,24 +255,48 @@ impl TcpHandle {
         addr: &SocketAddr,
         cb: CB,
     ) -> Result<crate::ConnectReq, Box<dyn std::error::Error>> {
+        let sockaddr = crate::to_sockaddr(addr)?;
         let mut req = crate::ConnectReq::new(cb)?;
-        let mut sockaddr: uv::sockaddr = unsafe { std::mem::zeroed() };
-        crate::fill_sockaddr(&mut sockaddr, addr)?;
-
         let result = crate::uvret(unsafe {
             uv_tcp_connect(
                 req.inner(),
                 self.handle,
-                &sockaddr as _,
+                uv_handle!(&sockaddr),
                 Some(crate::uv_connect_cb),
             )
         });
         if result.is_err() {
             req.destroy();
+        } else {
+            req.set_submitted();
         }
         result.map(|_| req).map_err(|e| Box::new(e) as _)
     }
 
+    /// Establish an IPv4 or IPv6 TCP connection, returning a future that resolves once the
+    /// connection has been established or a connection error happened. This is the async
+    /// counterpart to connect().
+    ///
+    /// Dropping the future before it resolves does not cancel the connection attempt: libuv will
+    /// still complete it, but the result will be discarded.
+    ///
+    /// Note: the future must be polled on the thread that is running the loop, and the loop must
+    /// be run for the future to resolve.
+    #[cfg(feature = "futures")]
+    pub fn connect_async(&mut self, addr: &SocketAddr) -> crate::ConnectFuture {
+        let (completer, future) = crate::futures::channel();
+        let mut completer = Some(completer);
+        let cb = move |_: crate::ConnectReq, status: crate::Result<u32>| {
+            if let Some(completer) = completer.take() {
+                completer.send(status.map(|_| ()).map_err(|e| Box::new(e) as _));
+            }
+        };
+        match self.connect(addr, cb) {
+            Ok(_) => future,
+            Err(e) => crate::ReqFuture::ready(Err(e)),
+        }
+    }
+
     /// Resets a TCP connection by sending a RST packet. This is accomplished by setting the
     /// SO_LINGER socket option with a linger interval of zero and then calling close(). Due to
     /// some platform inconsistencies, mixing of shutdown() and close_reset() calls is not allowed.
@@ -240,6 +359,12 @@ impl ToHandle for TcpHandle {
     }
 }
 
+impl std::fmt::Debug for TcpHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_handle().fmt_debug("TcpHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for TcpHandle {
     type Error = crate::ConversionError;
 
@@ -73,6 +73,11 @@ impl TtyHandle {
     /// To be called when the program exits. Resets TTY settings to default values for the next
     /// process to take over.
     ///
+    /// This is a process-global operation: it restores the mode of whichever TTY was last put
+    /// into a non-normal mode with set_mode(), regardless of the handle or loop. Programs that use
+    /// raw mode should call it before exiting (including from signal handlers), or the user's
+    /// terminal will be left in raw mode.
+    ///
     /// This function is async signal-safe on Unix platforms but can fail with error code EBUSY if
     /// you call it when execution is inside uv_tty_set_mode().
     pub fn reset_mode() -> crate::Result<()> {
@@ -157,6 +162,12 @@ impl ToHandle for TtyHandle {
     }
 }
 
+impl std::fmt::Debug for TtyHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_handle().fmt_debug("TtyHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for TtyHandle {
     type Error = crate::ConversionError;
 
@@ -1,7 +1,9 @@
 use crate::{FromInner, HandleTrait, Inner, IntoInner, ToHandle, NREAD};
+use std::cell::RefCell;
 use std::convert::{TryFrom, TryInto};
 use std::ffi::CString;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::rc::Rc;
 use uv::{
     uv_udp_bind, uv_udp_connect, uv_udp_get_send_queue_count, uv_udp_get_send_queue_size,
     uv_udp_getpeername, uv_udp_getsockname, uv_udp_init, uv_udp_init_ex, uv_udp_recv_start,
@@ -29,6 +31,14 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Flags passed to the UdpHandle::recv_start() callback
+    pub struct UdpRecvFlags: u32 {
+        /// The buffer was too small to hold the whole datagram, so it was truncated
+        const PARTIAL = uv::uv_udp_flags_UV_UDP_PARTIAL as _;
+    }
+}
+
 #[repr(u32)]
 pub enum Membership {
     Leave = uv::uv_membership_UV_LEAVE_GROUP as _,
@@ -40,9 +50,46 @@ callbacks! {
         handle: UdpHandle,
         nread: crate::Result<usize>,
         buf: crate::ReadonlyBuf,
-        addr: SocketAddr,
-        flags: UdpBindFlags
+        addr: Option<SocketAddr>,
+        flags: UdpRecvFlags
     );
+    pub UdpSendBatchCB(handle: UdpHandle, status: crate::Result<u32>);
+}
+
+/// Shared state of a send_batch()
+struct SendBatch {
+    remaining: usize,
+    status: crate::Result<u32>,
+    cb: UdpSendBatchCB<'static>,
+}
+
+impl SendBatch {
+    /// Marks count packets as complete, recording the first error. When no packets remain, the
+    /// callback is called.
+    fn complete(
+        batch: &Rc<RefCell<SendBatch>>,
+        handle: UdpHandle,
+        count: usize,
+        status: crate::Result<u32>,
+    ) {
+        let (mut cb, status) = {
+            let mut batch = batch.borrow_mut();
+            if batch.status.is_ok() {
+                if let Err(e) = status {
+                    batch.status = Err(e);
+                }
+            }
+            batch.remaining -= count;
+            if batch.remaining > 0 {
+                return;
+            }
+            (
+                std::mem::replace(&mut batch.cb, UdpSendBatchCB::Nil),
+                std::mem::replace(&mut batch.status, Ok(0)),
+            )
+        };
+        cb.call(handle, status);
+    }
 }
 
 /// Additional data to store on the stream
@@ -62,21 +109,24 @@ extern "C" fn uv_udp_recv_cb(
     let dataptr = crate::StreamHandle::get_data(uv_handle!(handle));
     if !dataptr.is_null() {
         if let super::UdpData(d) = unsafe { &mut (*dataptr).addl } {
-            let sockaddr = crate::build_socketaddr(addr);
-            if let Ok(sockaddr) = sockaddr {
-                let nread = if nread < 0 {
-                    Err(crate::Error::from_inner(nread as uv::uv_errno_t))
-                } else {
-                    Ok(nread as usize)
-                };
-                d.recv_cb.call(
-                    handle.into_inner(),
-                    nread,
-                    buf.into_inner(),
-                    sockaddr,
-                    UdpBindFlags::from_bits_truncate(flags),
-                );
-            }
+            // addr is null when there is nothing more to read (nread is 0) or on error
+            let sockaddr = if addr.is_null() {
+                None
+            } else {
+                crate::build_socketaddr(addr).ok()
+            };
+            let nread = if nread < 0 {
+                Err(crate::Error::from_inner(nread as uv::uv_errno_t))
+            } else {
+                Ok(nread as usize)
+            };
+            d.recv_cb.call(
+                handle.into_inner(),
+                nread,
+                buf.into_inner(),
+                sockaddr,
+                UdpRecvFlags::from_bits_truncate(flags),
+            );
         }
     }
 }
@@ -134,14 +184,21 @@ impl UdpHandle {
     }
 
     /// Bind the UDP handle to an IP address and port.
+    ///
+    /// flags can contain IPV6ONLY, to disable dual-stack support and only use IPv6, and
+    /// REUSEADDR, to allow multiple sockets to bind to the same address (SO_REUSEADDR, or
+    /// SO_REUSEPORT on BSDs and macOS).
+    ///
+    /// A handle can only be bound once: binding a handle that is already bound (including one that
+    /// was implicitly bound by send() or recv_start()) fails with the error reported by the
+    /// operating system, typically EINVAL.
     pub fn bind(
         &mut self,
         addr: &SocketAddr,
         flags: UdpBindFlags,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut sockaddr: uv::sockaddr = unsafe { std::mem::zeroed() };
-        crate::fill_sockaddr(&mut sockaddr, addr)?;
-        crate::uvret(unsafe { uv_udp_bind(self.handle, &sockaddr as _, flags.bits()) })
+        let sockaddr = crate::to_sockaddr(addr)?;
+        crate::uvret(unsafe { uv_udp_bind(self.handle, uv_handle!(&sockaddr), flags.bits()) })
             .map_err(|e| Box::new(e) as _)
     }
 
@@ -152,9 +209,8 @@ impl UdpHandle {
     /// ENOTCONN error.
     pub fn connect(&mut self, addr: Option<&SocketAddr>) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(addr) = addr {
-            let mut sockaddr: uv::sockaddr = unsafe { std::mem::zeroed() };
-            crate::fill_sockaddr(&mut sockaddr, addr)?;
-            crate::uvret(unsafe { uv_udp_connect(self.handle, &sockaddr as _) })
+            let sockaddr = crate::to_sockaddr(addr)?;
+            crate::uvret(unsafe { uv_udp_connect(self.handle, uv_handle!(&sockaddr)) })
         } else {
             crate::uvret(unsafe { uv_udp_connect(self.handle, std::ptr::null()) })
         }
@@ -179,6 +235,8 @@ impl UdpHandle {
     }
 
     /// Get the local IP and port of the UDP handle.
+    ///
+    /// Returns EINVAL if the handle has not been bound yet, regardless of platform.
     pub fn getsockname(&self) -> Result<SocketAddr, Box<dyn std::error::Error>> {
         let mut sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
         let mut sockaddr_len: std::os::raw::c_int =
@@ -191,45 +249,62 @@ impl UdpHandle {
             )
         })?;
 
-        crate::build_socketaddr(uv_handle!(&sockaddr))
+        crate::build_bound_socketaddr(uv_handle!(&sockaddr))
     }
 
-    /// Set membership for a multicast address
+    /// Converts an IP address string to a CString for libuv, returning EINVAL if it is not a valid
+    /// IPv4 or IPv6 address. IPv6 addresses may include a %scope suffix.
+    fn ip_cstring(addr: &str) -> Result<CString, Box<dyn std::error::Error>> {
+        let ip = addr.split('%').next().unwrap_or(addr);
+        if ip.parse::<IpAddr>().is_err() {
+            return Err(Box::new(crate::Error::EINVAL));
+        }
+        Ok(CString::new(addr)?)
+    }
+
+    /// Set membership for a multicast address. If interface_addr is None, the operating system
+    /// chooses the interface. Returns EINVAL if either address is not a valid IP address.
     pub fn set_membership(
         &mut self,
         multicast_addr: &str,
-        interface_addr: &str,
+        interface_addr: Option<&str>,
         membership: Membership,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let multicast_addr = CString::new(multicast_addr)?;
-        let interface_addr = CString::new(interface_addr)?;
+        let multicast_addr = UdpHandle::ip_cstring(multicast_addr)?;
+        let interface_addr = interface_addr.map(UdpHandle::ip_cstring).transpose()?;
         crate::uvret(unsafe {
             uv_udp_set_membership(
                 self.handle,
                 multicast_addr.as_ptr(),
-                interface_addr.as_ptr(),
+                interface_addr
+                    .as_ref()
+                    .map_or(std::ptr::null(), |addr| addr.as_ptr()),
                 membership as _,
             )
         })
         .map_err(|f| Box::new(f) as _)
     }
 
-    /// Set membership for a source-specific multicast group.
+    /// Set membership for a source-specific multicast group. If interface_addr is None, the
+    /// operating system chooses the interface. Returns EINVAL if any of the addresses is not a
+    /// valid IP address.
     pub fn set_source_membership(
         &mut self,
         multicast_addr: &str,
-        interface_addr: &str,
+        interface_addr: Option<&str>,
         source_addr: &str,
         membership: Membership,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let multicast_addr = CString::new(multicast_addr)?;
-        let interface_addr = CString::new(interface_addr)?;
-        let source_addr = CString::new(source_addr)?;
+        let multicast_addr = UdpHandle::ip_cstring(multicast_addr)?;
+        let interface_addr = interface_addr.map(UdpHandle::ip_cstring).transpose()?;
+        let source_addr = UdpHandle::ip_cstring(source_addr)?;
         crate::uvret(unsafe {
             uv_udp_set_source_membership(
                 self.handle,
                 multicast_addr.as_ptr(),
-                interface_addr.as_ptr(),
+                interface_addr
+                    .as_ref()
+                    .map_or(std::ptr::null(), |addr| addr.as_ptr()),
                 source_addr.as_ptr(),
                 membership as _,
             )
@@ -242,17 +317,20 @@ impl UdpHandle {
         crate::uvret(unsafe { uv_udp_set_multicast_loop(self.handle, if enable { 1 } else { 0 }) })
     }
 
-    /// Set the multicast ttl.
+    /// Set the multicast ttl. ttl must be in the range 1 through 255, otherwise libuv returns
+    /// EINVAL.
     pub fn set_multicast_ttl(&mut self, ttl: i32) -> crate::Result<()> {
         crate::uvret(unsafe { uv_udp_set_multicast_ttl(self.handle, ttl as _) })
     }
 
-    /// Set the multicast interface to send or receive data on.
+    /// Set the multicast interface to send or receive data on. interface_addr is the IP address of
+    /// the interface; IPv6 addresses may include a %scope suffix. Returns EINVAL if it is not a
+    /// valid IP address.
     pub fn set_multicast_interface(
         &mut self,
         interface_addr: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let interface_addr = CString::new(interface_addr)?;
+        let interface_addr = UdpHandle::ip_cstring(interface_addr)?;
         crate::uvret(unsafe {
             uv_udp_set_multicast_interface(self.handle, interface_addr.as_ptr())
         })
@@ -264,7 +342,8 @@ impl UdpHandle {
         crate::uvret(unsafe { uv_udp_set_broadcast(self.handle, if enable { 1 } else { 0 }) })
     }
 
-    /// Set the time to live.
+    /// Set the time to live. ttl must be in the range 1 through 255, otherwise libuv returns
+    /// EINVAL.
     pub fn set_ttl(&mut self, ttl: i32) -> crate::Result<()> {
         crate::uvret(unsafe { uv_udp_set_ttl(self.handle, ttl as _) })
     }
@@ -286,12 +365,24 @@ impl UdpHandle {
         bufs: &[impl crate::BufTrait],
         cb: CB,
     ) -> Result<crate::UdpSendReq, Box<dyn std::error::Error>> {
-        let mut req = crate::UdpSendReq::new(bufs, cb)?;
-        let mut sockaddr: uv::sockaddr = unsafe { std::mem::zeroed() };
+        let req = crate::UdpSendReq::new(bufs, cb)?;
+        self.submit_send(req, addr)
+    }
+
+    /// Submits a send request with uv_udp_send, destroying the request if the submission fails.
+    fn submit_send(
+        &self,
+        mut req: crate::UdpSendReq,
+        addr: Option<&SocketAddr>,
+    ) -> Result<crate::UdpSendReq, Box<dyn std::error::Error>> {
+        let mut sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
         let mut sockaddr_ptr: *const uv::sockaddr = std::ptr::null();
         if let Some(addr) = addr {
-            crate::fill_sockaddr(&mut sockaddr, addr)?;
-            sockaddr_ptr = &sockaddr as _;
+            if let Err(e) = crate::fill_sockaddr(uv_handle!(&mut sockaddr), addr) {
+                req.destroy();
+                return Err(e);
+            }
+            sockaddr_ptr = uv_handle!(&sockaddr);
         }
 
         let result = crate::uvret(unsafe {
@@ -299,19 +390,145 @@ impl UdpHandle {
                 req.inner(),
                 self.handle,
                 req.bufs_ptr,
-                bufs.len() as _,
+                req.bufs_len as _,
                 sockaddr_ptr,
                 Some(crate::uv_udp_send_cb),
             )
         });
         if result.is_err() {
             req.destroy();
+        } else {
+            req.set_submitted();
         }
         result.map(|_| req).map_err(|e| Box::new(e) as _)
     }
 
+    /// Send a batch of datagrams, each to its own address, for example to broadcast the same state
+    /// update to many clients. The callback is called once, after all of the packets have been
+    /// sent; its status is the first error that occurred, if any. The packets are owned by the
+    /// batch, so there are no lifetime requirements on the caller.
+    ///
+    /// On Linux, as many packets as possible are handed to the kernel with a single sendmmsg(2)
+    /// call on the handle's file descriptor (libuv does not expose sendmmsg), and any packets the
+    /// kernel did not accept are sent with send(). sendmmsg is only attempted when the send queue
+    /// is empty, so packets are never reordered with respect to earlier sends. The last packet is
+    /// always sent with send(), so the callback is called from the loop, just like any other send
+    /// callback. On all other platforms, every packet is sent with send().
+    ///
+    /// The handle must be connectionless. Returns EINVAL if packets is empty. If an error is
+    /// returned, the callback will not be called - but note that on Linux, some of the packets may
+    /// already have been sent.
+    pub fn send_batch<CB: Into<UdpSendBatchCB<'static>>>(
+        &self,
+        packets: Vec<(Vec<u8>, SocketAddr)>,
+        cb: CB,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if packets.is_empty() {
+            return Err(Box::new(crate::Error::EINVAL));
+        }
+
+        #[cfg(target_os = "linux")]
+        let sent = self.sendmmsg(&packets[..packets.len() - 1]);
+        #[cfg(not(target_os = "linux"))]
+        let sent = 0;
+
+        let total = packets.len() - sent;
+        let batch = Rc::new(RefCell::new(SendBatch {
+            remaining: total,
+            status: Ok(0),
+            cb: cb.into(),
+        }));
+        for (i, (data, addr)) in packets.into_iter().skip(sent).enumerate() {
+            let bufs = vec![unsafe { uv::uv_buf_init(data.as_ptr() as _, data.len() as _) }];
+
+            // The closure owns the data, which is dropped when the request is destroyed after the
+            // callback is called.
+            let mut data = Some(data);
+            let req_batch = batch.clone();
+            let cb = move |req: crate::UdpSendReq, status: crate::Result<u32>| {
+                data.take();
+                SendBatch::complete(&req_batch, req.handle(), 1, status);
+            };
+
+            let result = crate::UdpSendReq::new_with_uv_bufs(bufs, cb)
+                .map_err(|e| Box::new(e) as _)
+                .and_then(|req| self.submit_send(req, Some(&addr)));
+            if let Err(e) = result {
+                if i == 0 {
+                    return Err(e);
+                }
+
+                // Some packets are already in flight, so report the error through the callback
+                // once they complete. If it can't be converted to a libuv error, report EINVAL.
+                let status = Err(match e.downcast::<crate::Error>() {
+                    Ok(e) => *e,
+                    Err(_) => crate::Error::EINVAL,
+                });
+                SendBatch::complete(&batch, *self, total - i, status);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends as many of the packets as possible with a single sendmmsg(2) call, returning the
+    /// number of packets that were sent. Returns 0 if the send queue is not empty or on error: in
+    /// either case, the packets will be sent with send() instead.
+    #[cfg(target_os = "linux")]
+    fn sendmmsg(&self, packets: &[(Vec<u8>, SocketAddr)]) -> usize {
+        if packets.is_empty() || self.get_send_queue_count() > 0 {
+            return 0;
+        }
+        let fd = match self.get_fileno() {
+            Ok(fd) => fd,
+            Err(_) => return 0,
+        };
+
+        let mut addrs: Vec<libc::sockaddr_storage> = Vec::with_capacity(packets.len());
+        let mut iovecs: Vec<libc::iovec> = Vec::with_capacity(packets.len());
+        for (data, addr) in packets {
+            let mut sockaddr: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+            if crate::fill_sockaddr(uv_handle!(&mut sockaddr), addr).is_err() {
+                break;
+            }
+            addrs.push(sockaddr);
+            iovecs.push(libc::iovec {
+                iov_base: data.as_ptr() as _,
+                iov_len: data.len(),
+            });
+        }
+
+        let mut msgs: Vec<libc::mmsghdr> = addrs
+            .iter_mut()
+            .zip(iovecs.iter_mut())
+            .zip(packets)
+            .map(|((sockaddr, iovec), (_, addr))| {
+                let mut msg: libc::mmsghdr = unsafe { std::mem::zeroed() };
+                msg.msg_hdr.msg_name = sockaddr as *mut libc::sockaddr_storage as _;
+                msg.msg_hdr.msg_namelen = match addr {
+                    SocketAddr::V4(_) => std::mem::size_of::<libc::sockaddr_in>(),
+                    SocketAddr::V6(_) => std::mem::size_of::<libc::sockaddr_in6>(),
+                } as _;
+                msg.msg_hdr.msg_iov = iovec;
+                msg.msg_hdr.msg_iovlen = 1;
+                msg
+            })
+            .collect();
+
+        let ret = unsafe { libc::sendmmsg(fd as _, msgs.as_mut_ptr(), msgs.len() as _, 0) };
+        if ret < 0 {
+            0
+        } else {
+            ret as _
+        }
+    }
+
     /// Same as send(), but won’t queue a send request if it can’t be completed immediately.
     ///
+    /// Returns the number of bytes sent, or EAGAIN if the datagram could not be sent immediately
+    /// (for example, because the send queue is not empty). A datagram is never partially sent, so
+    /// on success the result is always the total length of bufs.
+    ///
     /// For connected UDP handles, addr must be set to None, otherwise it will return EISCONN
     /// error.
     ///
@@ -323,11 +540,14 @@ impl UdpHandle {
         bufs: &[impl crate::BufTrait],
     ) -> Result<i32, Box<dyn std::error::Error>> {
         let (bufs_ptr, bufs_len, bufs_capacity) = bufs.into_inner();
-        let mut sockaddr: uv::sockaddr = unsafe { std::mem::zeroed() };
+        let mut sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
         let mut sockaddr_ptr: *const uv::sockaddr = std::ptr::null();
         if let Some(addr) = addr {
-            crate::fill_sockaddr(&mut sockaddr, addr)?;
-            sockaddr_ptr = &sockaddr as _;
+            if let Err(e) = crate::fill_sockaddr(uv_handle!(&mut sockaddr), addr) {
+                unsafe { std::mem::drop(Vec::from_raw_parts(bufs_ptr, bufs_len, bufs_capacity)) };
+                return Err(e);
+            }
+            sockaddr_ptr = uv_handle!(&sockaddr);
         }
 
         let result = unsafe { uv_udp_try_send(self.handle, bufs_ptr, bufs_len as _, sockaddr_ptr) };
@@ -341,6 +561,11 @@ impl UdpHandle {
 
     /// Prepare for receiving data. If the socket has not previously been bound with bind() it is
     /// bound to 0.0.0.0 (the “all interfaces” IPv4 address) and a random port number.
+    ///
+    /// The recv_cb receives the sender's address, which is None when there is nothing more to
+    /// read (in which case nread is Ok(0)) or on error. Note that Ok(0) with Some address is a
+    /// valid, empty datagram. If the buffer was too small to hold the datagram, flags contains
+    /// PARTIAL. In every case, the callback is responsible for deallocating the buffer.
     pub fn recv_start<ACB: Into<crate::AllocCB<'static>>, CB: Into<RecvCB<'static>>>(
         &mut self,
         alloc_cb: ACB,
@@ -430,6 +655,12 @@ impl ToHandle for UdpHandle {
     }
 }
 
+impl std::fmt::Debug for UdpHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_handle().fmt_debug("UdpHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for UdpHandle {
     type Error = crate::ConversionError;
 
@@ -1,8 +1,8 @@
 use crate::{FromInner, HandleTrait, Inner, IntoInner};
 use std::convert::TryFrom;
 use uv::{
-    uv_timer_again, uv_timer_get_repeat, uv_timer_init, uv_timer_set_repeat, uv_timer_start,
-    uv_timer_stop, uv_timer_t,
+    uv_timer_again, uv_timer_get_due_in, uv_timer_get_repeat, uv_timer_init, uv_timer_set_repeat,
+    uv_timer_start, uv_timer_stop, uv_timer_t,
 };
 
 callbacks! {
@@ -69,6 +69,8 @@ impl TimerHandle {
         repeat: u64,
         cb: CB,
     ) -> crate::Result<()> {
+        debug_assert_loop_thread!(self);
+
         // uv_cb is either Some(uv_timer_cb) or None
         let cb = cb.into();
         let uv_cb = use_c_callback!(uv_timer_cb, cb);
@@ -114,6 +116,12 @@ impl TimerHandle {
     pub fn get_repeat(&self) -> u64 {
         unsafe { uv_timer_get_repeat(self.handle) }
     }
+
+    /// Get the timer due value or 0 if it has expired. The time is relative to Loop::now(), in
+    /// milliseconds.
+    pub fn get_due_in(&self) -> u64 {
+        unsafe { uv_timer_get_due_in(self.handle) }
+    }
 }
 
 impl FromInner<*mut uv_timer_t> for TimerHandle {
@@ -140,6 +148,12 @@ impl crate::ToHandle for TimerHandle {
     }
 }
 
+impl std::fmt::Debug for TimerHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::ToHandle::to_handle(self).fmt_debug("TimerHandle", f)
+    }
+}
+
 impl TryFrom<crate::Handle> for TimerHandle {
     type Error = crate::ConversionError;
 
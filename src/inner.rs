@@ -1,9 +1,9 @@
 //! Internal utilities
 use std::ffi::{CStr, CString};
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
 use std::str::FromStr;
 use std::string::ToString;
-use uv::{uv_ip4_addr, uv_ip4_name, uv_ip6_addr, uv_ip6_name, AF_INET, AF_INET6};
+use uv::{uv_ip4_addr, uv_ip4_name, uv_ip6_addr, uv_ip6_name, AF_INET, AF_INET6, AF_UNSPEC};
 
 /// An internal version of From<T>
 pub trait FromInner<T>: Sized {
@@ -95,7 +95,22 @@ where
     }
 }
 
-/// Fill a uv::sockaddr from a SocketAddr
+/// Convert a SocketAddr to a uv::sockaddr_storage, which is large enough to hold either a
+/// sockaddr_in or a sockaddr_in6. The result must outlive any libuv call that is given a pointer
+/// to it, so keep it in a local variable: uv_handle!(&sockaddr)
+pub(crate) fn to_sockaddr(
+    addr: &SocketAddr,
+) -> Result<uv::sockaddr_storage, Box<dyn std::error::Error>> {
+    let mut sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
+    fill_sockaddr(uv_handle!(&mut sockaddr), addr)?;
+    Ok(sockaddr)
+}
+
+/// Fill a uv::sockaddr from a SocketAddr. sockaddr must point to enough memory to hold a
+/// sockaddr_in6 if addr is an IPv6 address - prefer to_sockaddr(), which takes care of that.
+///
+/// IPv6 scope ids are only meaningful for link-local addresses, so a non-zero scope id on any
+/// other address is rejected with EINVAL.
 pub(crate) fn fill_sockaddr(
     sockaddr: *mut uv::sockaddr,
     addr: &SocketAddr,
@@ -109,14 +124,31 @@ pub(crate) fn fill_sockaddr(
                 .map_err(|e| Box::new(e) as _)
         }
         SocketAddr::V6(addr) => {
+            let segments = addr.ip().segments();
+            let link_local = (segments[0] & 0xffc0) == 0xfe80
+                || (segments[0] & 0xff0f) == 0xff02
+                || (segments[0] & 0xff0f) == 0xff01;
+            if addr.scope_id() != 0 && !link_local {
+                return Err(Box::new(crate::Error::EINVAL));
+            }
+
             let sockaddr_in6: *mut uv::sockaddr_in6 = sockaddr as _;
-            crate::uvret(unsafe { uv_ip6_addr(s.as_ptr(), addr.port() as _, sockaddr_in6) })
-                .map_err(|e| Box::new(e) as _)
+            crate::uvret(unsafe { uv_ip6_addr(s.as_ptr(), addr.port() as _, sockaddr_in6) })?;
+
+            // uv_ip6_addr only understands scope ids as part of the string, and only by interface
+            // name on Unix, so fill them in directly. The flowinfo is in network byte order.
+            #[cfg(unix)]
+            unsafe {
+                (*sockaddr_in6).sin6_scope_id = addr.scope_id() as _;
+                (*sockaddr_in6).sin6_flowinfo = addr.flowinfo().to_be() as _;
+            }
+            Ok(())
         }
     }
 }
 
-/// Create a SocketAddr from a uv::sockaddr_storage
+/// Create a SocketAddr from a uv::sockaddr_storage. Returns EINVAL if the address is not an IPv4
+/// or IPv6 address.
 pub(crate) fn build_socketaddr(
     sockaddr: *const uv::sockaddr,
 ) -> Result<SocketAddr, Box<dyn std::error::Error>> {
@@ -142,9 +174,99 @@ pub(crate) fn build_socketaddr(
                 crate::uvret(uv_ip6_name(sockaddr_in6, buf.as_mut_ptr(), 46))?;
                 let s = CStr::from_ptr(buf.as_ptr()).to_string_lossy();
                 let addr = Ipv6Addr::from_str(s.as_ref())?;
-                Ok(SocketAddr::new(IpAddr::V6(addr), port))
+
+                // uv_ip6_name doesn't include the scope id, so copy it (and the flowinfo, which is
+                // in network byte order) over directly.
+                #[cfg(unix)]
+                let addr = SocketAddrV6::new(
+                    addr,
+                    port,
+                    u32::from_be((*sockaddr_in6).sin6_flowinfo as _),
+                    (*sockaddr_in6).sin6_scope_id as _,
+                );
+                #[cfg(not(unix))]
+                let addr = SocketAddrV6::new(addr, port, 0, 0);
+                Ok(SocketAddr::V6(addr))
             }
         }
-        _ => Err(Box::new(crate::Error::ENOTSUP)),
+        _ => Err(Box::new(crate::Error::EINVAL)),
+    }
+}
+
+/// Create a SocketAddr from the result of getsockname(). Depending on the platform, getsockname()
+/// on a socket that has not been bound yet either fails with EINVAL, or succeeds with a zeroed
+/// address (0.0.0.0:0). This normalizes the latter case to EINVAL. Binding to port 0 assigns an
+/// ephemeral port, so a bound socket never reports port 0.
+pub(crate) fn build_bound_socketaddr(
+    sockaddr: *const uv::sockaddr,
+) -> Result<SocketAddr, Box<dyn std::error::Error>> {
+    if unsafe { (*sockaddr).sa_family as u32 } == AF_UNSPEC as u32 {
+        return Err(Box::new(crate::Error::EINVAL));
+    }
+
+    let addr = build_socketaddr(sockaddr)?;
+    if addr.port() == 0 {
+        return Err(Box::new(crate::Error::EINVAL));
+    }
+    Ok(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(addr: &str) -> Result<SocketAddr, Box<dyn std::error::Error>> {
+        let sockaddr = to_sockaddr(&addr.parse().unwrap())?;
+        build_bound_socketaddr(uv_handle!(&sockaddr))
+    }
+
+    fn is_einval(result: Result<SocketAddr, Box<dyn std::error::Error>>) -> bool {
+        result.unwrap_err().downcast_ref::<crate::Error>() == Some(&crate::Error::EINVAL)
+    }
+
+    #[test]
+    fn bound_ipv4() {
+        assert_eq!(
+            bound("127.0.0.1:8080").unwrap(),
+            "127.0.0.1:8080".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn bound_ipv6() {
+        assert_eq!(bound("[::1]:8080").unwrap(), "[::1]:8080".parse().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bound_ipv6_with_scope_id() {
+        let addr = SocketAddr::V6(SocketAddrV6::new("fe80::1".parse().unwrap(), 443, 0, 3));
+        let sockaddr = to_sockaddr(&addr).unwrap();
+        let bound = build_bound_socketaddr(uv_handle!(&sockaddr)).unwrap();
+        assert_eq!(bound, addr);
+        match bound {
+            SocketAddr::V6(bound) => assert_eq!(bound.scope_id(), 3),
+            SocketAddr::V4(_) => panic!("expected an IPv6 address"),
+        }
+    }
+
+    #[test]
+    fn unbound_port_is_einval() {
+        assert!(is_einval(bound("0.0.0.0:0")));
+    }
+
+    #[test]
+    fn unspecified_family_is_einval() {
+        let sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
+        assert!(is_einval(build_bound_socketaddr(uv_handle!(&sockaddr))));
+    }
+
+    #[test]
+    fn unknown_family_is_einval() {
+        let mut sockaddr: uv::sockaddr_storage = unsafe { std::mem::zeroed() };
+        let ptr: *mut uv::sockaddr = uv_handle!(&mut sockaddr);
+        // not a family that build_socketaddr() understands
+        unsafe { (*ptr).sa_family = 99 as _ };
+        assert!(is_einval(build_bound_socketaddr(uv_handle!(&sockaddr))));
     }
 }
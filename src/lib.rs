@@ -107,6 +107,15 @@ macro_rules! use_c_callback {
     };
 }
 
+/// In debug builds, panics if the current thread is not the thread that created the loop that
+/// the given handle belongs to. Compiled out in release builds.
+macro_rules! debug_assert_loop_thread {
+    ($handle:expr) => {
+        #[cfg(debug_assertions)]
+        crate::HandleTrait::get_loop(&crate::ToHandle::to_handle(&*$handle)).debug_assert_thread();
+    };
+}
+
 #[cfg(not(windows))]
 pub(crate) type NREAD = i64;
 #[cfg(windows)]
@@ -157,6 +166,19 @@ pub use shared_libs::*;
 pub mod misc;
 pub use misc::*;
 
+pub mod sync;
+pub use sync::*;
+
+#[cfg(feature = "futures")]
+pub mod futures;
+#[cfg(feature = "futures")]
+pub use futures::*;
+
+#[cfg(feature = "mock-clock")]
+pub mod mock_clock;
+#[cfg(feature = "mock-clock")]
+pub use mock_clock::*;
+
 /// Imports some things that most every program will need.
 pub mod prelude {
     pub use super::{
@@ -1,12 +1,16 @@
-use crate::{FromInner, HandleTrait, IntoInner};
+use crate::{FromInner, HandleTrait, Inner, IntoInner};
 use uv::{
     uv_backend_fd, uv_backend_timeout, uv_default_loop, uv_handle_t, uv_loop_alive, uv_loop_close,
-    uv_loop_configure, uv_loop_delete, uv_loop_fork, uv_loop_get_data, uv_loop_init, uv_loop_new,
-    uv_loop_option_UV_LOOP_BLOCK_SIGNAL, uv_loop_set_data, uv_loop_t, uv_now, uv_run, uv_run_mode,
+    uv_loop_configure, uv_loop_fork, uv_loop_get_data, uv_loop_init,
+    uv_loop_option_UV_LOOP_BLOCK_SIGNAL, uv_loop_option_UV_METRICS_IDLE_TIME, uv_loop_set_data,
+    uv_loop_t, uv_metrics_idle_time, uv_metrics_info, uv_metrics_t, uv_now, uv_run, uv_run_mode,
     uv_run_mode_UV_RUN_DEFAULT, uv_run_mode_UV_RUN_NOWAIT, uv_run_mode_UV_RUN_ONCE, uv_stop,
-    uv_update_time, uv_walk,
+    uv_timer_get_repeat, uv_update_time, uv_walk,
 };
 
+#[cfg(debug_assertions)]
+use uv::{uv_thread_equal, uv_thread_self, uv_thread_t};
+
 /// Mode used to run the loop.
 pub enum RunMode {
     /// Runs the event loop until there are no more active and referenced handles or requests.
@@ -35,26 +39,60 @@ impl IntoInner<uv_run_mode> for RunMode {
     }
 }
 
+/// Event loop metrics, as returned by Loop::metrics_info().
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Number of event loop iterations.
+    pub loop_count: u64,
+
+    /// Number of events that have been processed by the event handler.
+    pub events: u64,
+
+    /// Number of events that were waiting to be processed when the event provider was called.
+    pub events_waiting: u64,
+}
+
+impl FromInner<&uv_metrics_t> for Metrics {
+    fn from_inner(metrics: &uv_metrics_t) -> Metrics {
+        Metrics {
+            loop_count: metrics.loop_count,
+            events: metrics.events,
+            events_waiting: metrics.events_waiting,
+        }
+    }
+}
+
 /// Data that we need to track with the loop.
-#[derive(Default)]
 pub(crate) struct LoopData {
-    walk_cb: Option<Box<dyn FnMut(crate::Handle)>>,
+    /// Application data attached with Loop::set_data().
+    user_data: Option<Box<dyn std::any::Any>>,
+
+    /// Set once the loop has been closed with Loop::close(). Shared by every Loop that refers to
+    /// the same underlying loop.
+    closed: bool,
+
+    /// The thread that created the loop. Only tracked in debug builds.
+    #[cfg(debug_assertions)]
+    thread: uv_thread_t,
 }
 
-/// Callback for uv_walk
-extern "C" fn uv_walk_cb(handle: *mut uv_handle_t, _: *mut ::std::os::raw::c_void) {
-    let handle: crate::Handle = handle.into_inner();
-    let r#loop = handle.get_loop();
-    let dataptr = r#loop.get_data();
-    if !dataptr.is_null() {
-        unsafe {
-            if let Some(f) = (*dataptr).walk_cb.as_mut() {
-                f(handle);
-            }
+impl Default for LoopData {
+    fn default() -> Self {
+        LoopData {
+            user_data: None,
+            closed: false,
+            #[cfg(debug_assertions)]
+            thread: unsafe { uv_thread_self() },
         }
     }
 }
 
+/// Callback for uv_walk. arg points at the closure passed to Loop::walk().
+extern "C" fn uv_walk_cb(handle: *mut uv_handle_t, arg: *mut ::std::os::raw::c_void) {
+    let f = unsafe { &mut *(arg as *mut &mut dyn FnMut(crate::Handle)) };
+    f(handle.into_inner());
+}
+
 /// The event loop is the central part of libuv’s functionality. It takes care of polling for i/o
 /// and scheduling callbacks to be run based on different sources of events.
 pub struct Loop {
@@ -65,14 +103,16 @@ pub struct Loop {
 impl Loop {
     /// Creates a new Loop.
     pub fn new() -> crate::Result<Loop> {
-        let handle = unsafe { uv_loop_new() };
+        // The loop is allocated here, rather than with uv_loop_new(), so that it can be freed
+        // without knowing which allocator libuv was configured to use.
+        let handle = unsafe { std::alloc::alloc_zeroed(Self::layout()) } as *mut uv_loop_t;
         if handle.is_null() {
             return Err(crate::Error::ENOMEM);
         }
 
         let ret = unsafe { uv_loop_init(handle) };
         if ret < 0 {
-            unsafe { uv_loop_delete(handle) };
+            unsafe { std::alloc::dealloc(handle as _, Self::layout()) };
             return Err(crate::Error::from_inner(ret as uv::uv_errno_t));
         }
 
@@ -103,13 +143,22 @@ impl Loop {
             handle,
             should_drop: false,
         };
-        if r#loop.get_data().is_null() {
+        let dataptr = r#loop.get_data();
+        if dataptr.is_null() {
             r#loop.initialize_data();
+        } else {
+            // uv_default_loop() re-initializes the default loop if it was closed, keeping its data
+            unsafe { (*dataptr).closed = false };
         }
 
         Ok(r#loop)
     }
 
+    /// The memory layout of loops created with new().
+    fn layout() -> std::alloc::Layout {
+        std::alloc::Layout::new::<uv_loop_t>()
+    }
+
     /// Initialize the loop's data.
     fn initialize_data(&mut self) {
         let data: Box<LoopData> = Box::new(Default::default());
@@ -122,6 +171,12 @@ impl Loop {
         unsafe { uv_loop_get_data(self.handle) as _ }
     }
 
+    /// Returns true if the loop has been closed with close().
+    fn is_closed(&self) -> bool {
+        let dataptr = self.get_data();
+        !dataptr.is_null() && unsafe { (*dataptr).closed }
+    }
+
     /// Free the loop's data.
     fn free_data(&mut self) {
         let ptr = self.get_data();
@@ -129,6 +184,74 @@ impl Loop {
         unsafe { uv_loop_set_data(self.handle, std::ptr::null_mut()) };
     }
 
+    /// Attach application data to the loop, replacing (and dropping) any data that was previously
+    /// attached. The data can be used with with_data() from any Loop that refers to the same
+    /// underlying loop, such as the one returned by a handle's get_loop() in a callback. It is
+    /// dropped when the owning Loop is dropped.
+    pub fn set_data<T: 'static>(&mut self, data: Box<T>) {
+        let dataptr = self.get_data();
+        if !dataptr.is_null() {
+            unsafe { (*dataptr).user_data = Some(data) };
+        }
+    }
+
+    /// Call f with the data attached with set_data(), returning its result. Returns None, without
+    /// calling f, if no data is attached or if the attached data is not of type T.
+    ///
+    /// The data is detached while f runs, so that f cannot drop it out from under itself (for
+    /// example, by calling set_data() on a clone of this Loop). During that time, with_data() and
+    /// take_data() behave as if no data was attached. If f attaches new data, the new data is kept
+    /// and the old data is dropped once f returns; otherwise, the data is attached again.
+    pub fn with_data<T: 'static, R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let dataptr = self.get_data();
+        if dataptr.is_null() {
+            return None;
+        }
+
+        let mut user_data = unsafe { (*dataptr).user_data.take() }?;
+        let result = user_data.downcast_mut::<T>().map(f);
+
+        let slot = unsafe { &mut (*dataptr).user_data };
+        if slot.is_none() {
+            *slot = Some(user_data);
+        }
+        result
+    }
+
+    /// Detach and return the data attached with set_data(). If the attached data is not of type
+    /// T, it is left attached and None is returned.
+    pub fn take_data<T: 'static>(&mut self) -> Option<Box<T>> {
+        let dataptr = self.get_data();
+        if dataptr.is_null() {
+            return None;
+        }
+        let user_data = unsafe { &mut (*dataptr).user_data };
+        match user_data.take().map(|d| d.downcast::<T>()) {
+            Some(Ok(d)) => Some(d),
+            Some(Err(d)) => {
+                *user_data = Some(d);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Panics if the current thread is not the thread that created the loop. Handles are bound to
+    /// the thread of the loop they were created on; driving them from any other thread is
+    /// undefined behaviour. This check is only performed in debug builds.
+    #[cfg(debug_assertions)]
+    pub(crate) fn debug_assert_thread(&self) {
+        let dataptr = self.get_data();
+        if !dataptr.is_null() {
+            let current = unsafe { uv_thread_self() };
+            let same_thread = unsafe { uv_thread_equal(&(*dataptr).thread as _, &current as _) };
+            assert!(
+                same_thread != 0,
+                "libuv handle used from a thread other than the one that created its loop"
+            );
+        }
+    }
+
     /// Block a signal when polling for new events. The second argument to configure() is the
     /// signal number.
     ///
@@ -140,17 +263,64 @@ impl Loop {
         })
     }
 
+    /// Accumulate the amount of idle time the event loop spends in the event provider. This must
+    /// be called before metrics_idle_time() will return anything meaningful, and should be called
+    /// before run().
+    pub fn configure_metrics_idle_time(&mut self) -> crate::Result<()> {
+        crate::uvret(unsafe { uv_loop_configure(self.handle, uv_loop_option_UV_METRICS_IDLE_TIME) })
+    }
+
+    /// Retrieve the amount of time the event loop has been idle in the kernel’s event provider
+    /// (e.g. epoll_wait), in nanoseconds. The call is thread safe.
+    ///
+    /// The return value is the accumulated time spent idle in the kernel’s event provider starting
+    /// from when the loop was configured to collect the idle time with
+    /// configure_metrics_idle_time(). If the loop wasn't configured, this returns 0.
+    ///
+    /// Note: The event loop will not begin accumulating the event provider’s idle time until
+    /// calling run().
+    pub fn metrics_idle_time(&self) -> u64 {
+        unsafe { uv_metrics_idle_time(self.handle) }
+    }
+
+    /// Retrieve the loop's metrics: the number of loop iterations, and the number of events that
+    /// have been processed and were waiting to be processed.
+    pub fn metrics_info(&self) -> crate::Result<Metrics> {
+        let mut metrics: uv_metrics_t = unsafe { std::mem::zeroed() };
+        crate::uvret(unsafe { uv_metrics_info(self.handle, &mut metrics as _) })?;
+        Ok((&metrics).into_inner())
+    }
+
     /// Releases all internal loop resources. Call this function only when the loop has finished
     /// executing and all open handles and requests have been closed, or it will return
-    /// Error::EBUSY.  After this function returns, the user can free the memory allocated for the
-    /// loop.
+    /// Error::EBUSY.
+    ///
+    /// Closing a loop that has already been closed does nothing. The memory of a loop created with
+    /// new() is freed when that Loop is dropped, so other Loops referring to it (such as clones,
+    /// or the Loop returned by a handle's get_loop()) remain valid until then. Once closed, run()
+    /// and fork() return Error::EINVAL, and walk() does not find any handles.
     pub fn close(&mut self) -> crate::Result<()> {
-        crate::uvret(unsafe { uv_loop_close(self.handle) })
+        if self.is_closed() {
+            return Ok(());
+        }
+
+        // uv_loop_close() clobbers the whole loop in debug builds of libuv, including its data
+        let dataptr = self.get_data();
+        crate::uvret(unsafe { uv_loop_close(self.handle) })?;
+        unsafe { uv_loop_set_data(self.handle, dataptr as _) };
+        if !dataptr.is_null() {
+            unsafe { (*dataptr).closed = true };
+        }
+        Ok(())
     }
 
     /// This function runs the event loop. It will act differently depending on the specified mode.
     /// run() is not reentrant. It must not be called from a callback.
     pub fn run(&mut self, mode: RunMode) -> crate::Result<i32> {
+        if self.is_closed() {
+            return Err(crate::Error::EINVAL);
+        }
+
         let ret = unsafe { uv_run(self.handle, mode.into_inner()) };
         if ret < 0 {
             Err(crate::Error::from_inner(ret as uv::uv_errno_t))
@@ -159,10 +329,38 @@ impl Loop {
         }
     }
 
+    /// Run a single, non-blocking iteration of the loop: equivalent to run(NoWait). This is meant
+    /// for embedding the loop in another event loop or reactor that has already determined that
+    /// the loop is ready, by polling backend_fd() for readability with a timeout of
+    /// backend_timeout(). The full embed cycle is:
+    ///
+    ///   1. Call run_ready() once to process any work that is already pending.
+    ///   2. Wait for backend_fd() to become readable, with a timeout of backend_timeout()
+    ///      milliseconds (-1 meaning no timeout).
+    ///   3. Call run_ready() and, if it returned true, go back to step 2.
+    ///
+    /// Returns true if there are still active handles or requests, meaning run_ready() should be
+    /// called again once the loop is ready. See examples/embed.rs for a complete example. Like
+    /// run(), this must not be called from a callback.
+    pub fn run_ready(&mut self) -> crate::Result<bool> {
+        self.run(RunMode::NoWait).map(|alive| alive != 0)
+    }
+
+    /// Runs the loop in Once mode until done() returns true. Returns EINVAL if the loop runs out of
+    /// work before that happens. Like run(), this must not be called from a callback.
+    pub(crate) fn run_until(&mut self, done: impl Fn() -> bool) -> crate::Result<()> {
+        while !done() {
+            if self.run(RunMode::Once)? == 0 && !done() {
+                return Err(crate::Error::EINVAL);
+            }
+        }
+        Ok(())
+    }
+
     /// Returns true if there are referenced active handles, active requests or closing handles in
     /// the loop.
     pub fn is_alive(&self) -> bool {
-        unsafe { uv_loop_alive(self.handle) != 0 }
+        !self.is_closed() && unsafe { uv_loop_alive(self.handle) != 0 }
     }
 
     /// Stop the event loop, causing run() to end as soon as possible. This will happen not sooner
@@ -174,8 +372,9 @@ impl Loop {
 
     /// Get backend file descriptor. Only kqueue, epoll and event ports are supported.
     ///
-    /// This can be used in conjunction with run(NoWait) to poll in one thread and run the event
-    /// loop’s callbacks in another see test/test-embed.c for an example.
+    /// This can be used in conjunction with run_ready() (or run(NoWait)) to poll in one thread and
+    /// run the event loop’s callbacks in another, or to embed the loop in another event loop. See
+    /// examples/embed.rs for an example.
     ///
     /// Note: Embedding a kqueue fd in another kqueue pollset doesn’t work on all platforms. It’s
     /// not an error to add the fd but it never generates events.
@@ -185,6 +384,9 @@ impl Loop {
 
     /// Get the poll timeout. The return value is in milliseconds, or -1 for no timeout.
     pub fn backend_timeout(&self) -> i32 {
+        if self.is_closed() {
+            return 0;
+        }
         unsafe { uv_backend_timeout(self.handle) as _ }
     }
 
@@ -207,15 +409,167 @@ impl Loop {
         unsafe { uv_update_time(self.handle) }
     }
 
-    /// Walk the list of handles.
-    pub fn walk(&self, cb: impl FnMut(crate::Handle) + 'static) {
-        let cb = Box::new(cb);
-        let dataptr = self.get_data();
-        if !dataptr.is_null() {
-            unsafe { (*dataptr).walk_cb = Some(cb) };
+    /// Walk the list of handles, calling cb for each one. This is useful for finding handles that
+    /// are still open before closing the loop. The walk is synchronous, so cb may borrow from the
+    /// caller's stack.
+    pub fn walk(&self, mut cb: impl FnMut(crate::Handle)) {
+        if self.is_closed() {
+            return;
+        }
+
+        let mut cb: &mut dyn FnMut(crate::Handle) = &mut cb;
+        unsafe {
+            uv_walk(
+                self.handle,
+                Some(uv_walk_cb),
+                &mut cb as *mut &mut dyn FnMut(crate::Handle) as _,
+            )
+        };
+    }
+
+    /// Walk the list of handles, reporting whether each handle is referenced, whether it is
+    /// active, and whether it is closing. Handles that are both referenced and active are the ones
+    /// keeping the loop alive, which makes this useful for debugging why run() does not return.
+    pub fn walk_detailed(&self, mut cb: impl FnMut(crate::Handle, bool, bool, bool)) {
+        self.walk(move |handle| {
+            let has_ref = handle.has_ref();
+            let is_active = handle.is_active();
+            let is_closing = handle.is_closing();
+            cb(handle, has_ref, is_active, is_closing)
+        });
+    }
+
+    /// Close every handle that is still open in the loop, run the loop until all of the close
+    /// callbacks have fired, and then close the loop. Returns the types of the handles that were
+    /// still open (and not already closing), which is a useful hint for tracking down leaked
+    /// handles.
+    ///
+    /// Handles that are already closing are left alone, but the loop will still wait for them to
+    /// finish closing. If the loop cannot be closed afterwards (for example, because there are
+    /// still pending requests), the error from close() is returned.
+    ///
+    /// As with close(), calling this on a loop that has already been closed does nothing.
+    ///
+    /// Like run(), this function must not be called from a callback.
+    pub fn force_close(&mut self) -> crate::Result<Vec<crate::HandleType>> {
+        let mut leaked = Vec::new();
+        if self.is_closed() {
+            return Ok(leaked);
+        }
+
+        for mut handle in self.handles() {
+            if !handle.is_closing() {
+                leaked.push(handle.get_type());
+                handle.close(());
+            }
         }
 
-        unsafe { uv_walk(self.handle, Some(uv_walk_cb), std::ptr::null_mut()) };
+        self.run(RunMode::Default)?;
+        self.close()?;
+        Ok(leaked)
+    }
+
+    /// Runs the event loop like run(RunMode::Default), but reduces the loop's hold on the process
+    /// once the application has been idle for idle_threshold_ms milliseconds.
+    ///
+    /// An iteration of the loop is considered idle if it spent less than a tenth of its time
+    /// running callbacks (the rest being spent waiting for events). Once the loop has been idle
+    /// for idle_threshold_ms, "background" handles - active, referenced TimerHandles with a
+    /// non-zero repeat - are unref'd. If nothing else is keeping the loop alive, run_adaptive()
+    /// then returns, letting the process sleep or suspend instead of waking up for periodic
+    /// housekeeping. As soon as an iteration is busy again, the background handles are re-ref'd.
+    /// Background handles are always re-ref'd before this function returns, so calling
+    /// run_adaptive() (or run()) again resumes them.
+    ///
+    /// Tradeoffs: background timers do not fire while the caller is away from the loop, and the
+    /// idle heuristic may treat a steady trickle of very cheap callbacks as idleness. Handles that
+    /// are unref'd by the caller are left alone.
+    ///
+    /// This function enables the loop's idle time metrics (UV_METRICS_IDLE_TIME), which it uses to
+    /// measure idleness. Like run(), it must not be called from a callback.
+    pub fn run_adaptive(&mut self, idle_threshold_ms: u64) -> crate::Result<()> {
+        self.configure_metrics_idle_time()?;
+
+        let threshold = idle_threshold_ms.saturating_mul(1_000_000);
+        let mut idle_since = crate::hrtime();
+        let mut background = false;
+        loop {
+            let idle_before = self.metrics_idle_time();
+            let start = crate::hrtime();
+            let result = self.run(RunMode::Once);
+            let now = crate::hrtime();
+            let idle = self.metrics_idle_time().saturating_sub(idle_before);
+            let busy = (now - start).saturating_sub(idle);
+
+            let alive = match result {
+                Ok(alive) => alive != 0,
+                Err(e) => {
+                    self.ref_background(&mut background);
+                    return Err(e);
+                }
+            };
+
+            if busy.saturating_mul(10) > now - start {
+                idle_since = now;
+                self.ref_background(&mut background);
+            } else if !background && now - idle_since >= threshold {
+                background = self.unref_background();
+            }
+
+            if !alive {
+                self.ref_background(&mut background);
+                return Ok(());
+            }
+        }
+    }
+
+    /// Unref's all active, referenced, repeating timers, marking them as background handles in
+    /// their data. Returns true if any handles were unref'd.
+    fn unref_background(&self) -> bool {
+        let mut background = false;
+        for mut handle in self.handles() {
+            let dataptr = crate::Handle::get_data(handle.inner());
+            if !dataptr.is_null()
+                && handle.get_type() == crate::HandleType::TIMER
+                && handle.is_active()
+                && handle.has_ref()
+                && unsafe { uv_timer_get_repeat(handle.inner() as _) } > 0
+            {
+                handle.unref();
+                unsafe { (*dataptr).background = true };
+                background = true;
+            }
+        }
+        background
+    }
+
+    /// Re-ref's the handles that were marked by unref_background(), and clears the mark. Handles
+    /// that have been closed since are no longer in the loop (and their data, along with the mark,
+    /// has been freed), so a new handle that happens to reuse the address of a closed one is never
+    /// re-ref'd by mistake.
+    fn ref_background(&self, background: &mut bool) {
+        if !*background {
+            return;
+        }
+        *background = false;
+
+        for mut handle in self.handles() {
+            let dataptr = crate::Handle::get_data(handle.inner());
+            if dataptr.is_null() || !unsafe { (*dataptr).background } {
+                continue;
+            }
+            unsafe { (*dataptr).background = false };
+            if !handle.is_closing() {
+                handle.r#ref();
+            }
+        }
+    }
+
+    /// Collects all of the handles in the loop.
+    fn handles(&self) -> Vec<crate::Handle> {
+        let mut handles = Vec::new();
+        self.walk(|handle| handles.push(handle));
+        handles
     }
 
     /// Reinitialize any kernel state necessary in the child process after a fork(2) system call.
@@ -248,6 +602,9 @@ impl Loop {
     /// and restarted. On all other platforms, they will continue to work normally without any
     /// further intervention.
     pub fn fork(&mut self) -> crate::Result<()> {
+        if self.is_closed() {
+            return Err(crate::Error::EINVAL);
+        }
         crate::uvret(unsafe { uv_loop_fork(self.handle) })
     }
 }
@@ -279,11 +636,13 @@ impl IntoInner<*mut uv_loop_t> for &Loop {
 impl Drop for Loop {
     fn drop(&mut self) {
         if self.should_drop {
-            if !self.handle.is_null() {
-                self.free_data();
-                unsafe { uv_loop_delete(self.handle) };
+            if self.close().is_err() {
+                // handles or requests still refer to the loop, so leak it rather than free memory
+                // that they may still use
+                return;
             }
-            self.handle = std::ptr::null_mut();
+            self.free_data();
+            unsafe { std::alloc::dealloc(self.handle as _, Self::layout()) };
         }
     }
 }
@@ -108,7 +108,7 @@ impl FromInner<uv_rusage_t> for ResourceUsage {
     }
 }
 
-/// Data type for CPU information.
+/// Data type for CPU information. Times are in milliseconds.
 pub struct CpuInfo {
     pub model: String,
     pub speed: i32,
@@ -206,13 +206,13 @@ pub fn set_process_title(title: &str) -> Result<(), Box<dyn std::error::Error>>
     crate::uvret(unsafe { uv_set_process_title(title.as_ptr()) }).map_err(|e| Box::new(e) as _)
 }
 
-/// Gets the resident set size (RSS) for the current process.
+/// Gets the resident set size (RSS) for the current process, in bytes.
 pub fn resident_set_memory() -> crate::Result<usize> {
     let mut rss = 0u64;
     crate::uvret(unsafe { uv_resident_set_memory(&mut rss as _) }).map(|_| rss as _)
 }
 
-/// Gets the current system uptime.
+/// Gets the current system uptime, in seconds.
 pub fn uptime() -> crate::Result<f64> {
     let mut uptime = 0f64;
     crate::uvret(unsafe { uv_uptime(&mut uptime as _) }).map(|_| uptime)
@@ -227,21 +227,27 @@ pub fn getrusage() -> crate::Result<ResourceUsage> {
     crate::uvret(unsafe { uv_getrusage(&mut usage as _) }).map(|_| usage.into_inner())
 }
 
-/// Gets information about the CPUs on the system.
+/// Gets information about the CPUs on the system. The CPU times are in milliseconds. Everything
+/// is copied out of libuv's array before it is freed, so the result has no ties to libuv memory.
 pub fn cpu_info() -> crate::Result<Vec<CpuInfo>> {
-    let mut infos: *mut uv_cpu_info_t = unsafe { std::mem::zeroed() };
+    let mut infos: *mut uv_cpu_info_t = std::ptr::null_mut();
     let mut count: std::os::raw::c_int = 0;
     crate::uvret(unsafe { uv_cpu_info(&mut infos as _, &mut count as _) })?;
 
-    let result = unsafe { std::slice::from_raw_parts(infos, count as _) }
-        .iter()
-        .map(|info| info.into_inner())
-        .collect();
+    let result = if infos.is_null() {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(infos, count as _) }
+            .iter()
+            .map(|info| info.into_inner())
+            .collect()
+    };
     unsafe { uv_free_cpu_info(infos, count as _) };
     Ok(result)
 }
 
-/// Gets the load average. See: https://en.wikipedia.org/wiki/Load_(computing)
+/// Gets the 1, 5 and 15 minute load averages, in that order. See:
+/// https://en.wikipedia.org/wiki/Load_(computing)
 ///
 /// Note: Returns [0,0,0] on Windows (i.e., it’s not implemented).
 pub fn loadavg() -> [f64; 3] {
@@ -250,12 +256,13 @@ pub fn loadavg() -> [f64; 3] {
     return avg;
 }
 
-/// Gets memory information (in bytes).
+/// Gets the amount of free memory available in the system, as reported by the kernel (in bytes).
 pub fn get_free_memory() -> u64 {
     unsafe { uv_get_free_memory() }
 }
 
-/// Gets memory information (in bytes).
+/// Gets the total amount of physical memory in the system (in bytes). This does not take limits
+/// imposed on the process (such as cgroups) into account; see get_constrained_memory() for that.
 pub fn get_total_memory() -> u64 {
     unsafe { uv_get_total_memory() }
 }
@@ -280,6 +287,12 @@ pub fn hrtime() -> u64 {
     unsafe { uv_hrtime() }
 }
 
+/// Same as hrtime(), but returned as a Duration since the same arbitrary point in the past. The
+/// difference between two results is the elapsed time between the calls.
+pub fn hrtime_duration() -> std::time::Duration {
+    std::time::Duration::from_nanos(hrtime())
+}
+
 /// Cross-platform implementation of gettimeofday(2). The timezone argument to gettimeofday() is
 /// not supported, as it is considered obsolete.
 pub fn gettimeofday() -> crate::Result<TimeVal> {
@@ -291,3 +304,128 @@ pub fn gettimeofday() -> crate::Result<TimeVal> {
 pub fn sleep(msec: u32) {
     unsafe { uv_sleep(msec) };
 }
+
+/// Detach the current process from its controlling terminal and run it in the background. This
+/// performs the traditional double-fork: the process forks, the child becomes the leader of a new
+/// session with setsid(2), then forks again so that it can never reacquire a controlling terminal.
+/// Finally, stdin, stdout and stderr are redirected to /dev/null. The current working directory
+/// and umask are left untouched.
+///
+/// Only the final grandchild returns Ok from this function. The original process waits until the
+/// grandchild has been set up, and then exits with status 0; the intermediate child also exits
+/// with status 0. If any step fails after the first fork, the process it failed in reports the
+/// error to the original process through a pipe and exits with status 1, and the original process
+/// returns the error instead of exiting. The original process also returns an error (EPIPE) if
+/// the child dies without reporting anything.
+///
+/// Warning: This function must be called before creating any Loop (including the default loop)
+/// or starting any threads. libuv's internal state, such as its threadpool and the loop's backend
+/// file descriptor, does not survive a fork in a usable way. Create a fresh Loop after
+/// daemonize() returns rather than calling Loop::fork() on a loop created before.
+///
+/// This function is not implemented on Windows, where it returns ENOSYS. Use a service wrapper
+/// instead.
+#[cfg(unix)]
+pub fn daemonize() -> crate::Result<()> {
+    unsafe {
+        let mut fds = [0; 2];
+        if libc::pipe(fds.as_mut_ptr()) == -1 {
+            return Err(crate::Error::last_os_error());
+        }
+        let (read_fd, write_fd) = (fds[0], fds[1]);
+
+        match libc::fork() {
+            -1 => {
+                let err = crate::Error::last_os_error();
+                libc::close(read_fd);
+                libc::close(write_fd);
+                return Err(err);
+            }
+            0 => {
+                libc::close(read_fd);
+            }
+            child => {
+                libc::close(write_fd);
+                let result = read_daemonize_result(read_fd);
+                libc::close(read_fd);
+                if result.is_ok() {
+                    libc::_exit(0);
+                }
+                libc::waitpid(child, std::ptr::null_mut(), 0);
+                return result;
+            }
+        }
+
+        if libc::setsid() == -1 {
+            report_daemonize_error(write_fd, crate::Error::last_os_error());
+        }
+
+        match libc::fork() {
+            -1 => report_daemonize_error(write_fd, crate::Error::last_os_error()),
+            0 => {}
+            _ => libc::_exit(0),
+        }
+
+        let devnull = libc::open(b"/dev/null\0".as_ptr() as _, libc::O_RDWR);
+        if devnull == -1 {
+            report_daemonize_error(write_fd, crate::Error::last_os_error());
+        }
+        for fd in &[libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+            if libc::dup2(devnull, *fd) == -1 {
+                report_daemonize_error(write_fd, crate::Error::last_os_error());
+            }
+        }
+        if devnull > libc::STDERR_FILENO {
+            libc::close(devnull);
+        }
+
+        write_daemonize_code(write_fd, 0);
+        libc::close(write_fd);
+    }
+
+    Ok(())
+}
+
+/// Writes a result code for daemonize() to the pipe: 0 for success, or an Error's code.
+#[cfg(unix)]
+unsafe fn write_daemonize_code(fd: i32, code: i32) {
+    let size = std::mem::size_of::<i32>();
+    while libc::write(fd, &code as *const i32 as _, size) == -1
+        && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted
+    {}
+}
+
+/// Reports a daemonize() error to the original process, and exits.
+#[cfg(unix)]
+unsafe fn report_daemonize_error(fd: i32, err: crate::Error) -> ! {
+    write_daemonize_code(fd, err.code());
+    libc::_exit(1)
+}
+
+/// Waits for the result of daemonize() to be reported by the grandchild (or by the process that
+/// failed).
+#[cfg(unix)]
+unsafe fn read_daemonize_result(fd: i32) -> crate::Result<()> {
+    let mut code: i32 = 0;
+    let size = std::mem::size_of::<i32>();
+    loop {
+        match libc::read(fd, &mut code as *mut i32 as _, size) {
+            -1 if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted => {}
+            n if n == size as isize && code == 0 => return Ok(()),
+            n if n == size as isize => {
+                return Err(crate::Error::from_inner(code as uv::uv_errno_t))
+            }
+            // the child died without reporting anything
+            _ => return Err(crate::Error::EPIPE),
+        }
+    }
+}
+
+/// Detach the current process from its controlling terminal and run it in the background.
+///
+/// This function is not implemented on Windows, where it returns ENOSYS. Use a service wrapper
+/// instead.
+#[cfg(windows)]
+pub fn daemonize() -> crate::Result<()> {
+    Err(crate::Error::ENOSYS)
+}
@@ -1,9 +1,12 @@
 use crate::{FromInner, IntoInner};
-use std::ffi::CStr;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
 use uv::{
-    uv_os_free_passwd, uv_os_get_passwd, uv_os_gethostname, uv_os_getpid, uv_os_getppid,
-    uv_os_getpriority, uv_os_setpriority, uv_os_uname, uv_passwd_t, uv_utsname_t,
-    UV_MAXHOSTNAMESIZE,
+    uv_env_item_t, uv_os_environ, uv_os_free_environ, uv_os_free_passwd, uv_os_get_passwd,
+    uv_os_getenv, uv_os_gethostname, uv_os_getpid, uv_os_getppid, uv_os_getpriority, uv_os_homedir,
+    uv_os_setenv, uv_os_setpriority, uv_os_tmpdir, uv_os_uname, uv_os_unsetenv, uv_passwd_t,
+    uv_utsname_t, UV_MAXHOSTNAMESIZE,
 };
 
 /// Cross platform representation of a pid_t.
@@ -86,6 +89,30 @@ impl FromInner<uv_utsname_t> for SystemInfo {
     }
 }
 
+/// Calls f with a buffer and its size, growing the buffer if f fails with ENOBUFS. f must behave
+/// like uv_os_getenv(): on success, size is set to the length of the string (not including the
+/// null), and on ENOBUFS, size is set to the required size (including the null).
+fn get_string(
+    initial_size: usize,
+    f: impl Fn(*mut c_char, *mut usize) -> c_int,
+) -> crate::Result<String> {
+    let mut size = initial_size;
+    let mut buf: Vec<u8> = Vec::new();
+    loop {
+        buf.resize(size, 0);
+        let mut len = size;
+        match crate::uvret(f(buf.as_mut_ptr() as _, &mut len as _)) {
+            Ok(_) => {
+                buf.truncate(len);
+                return Ok(String::from_utf8_lossy(&buf).into_owned());
+            }
+            Err(crate::Error::ENOBUFS) if len > size => size = len,
+            Err(crate::Error::ENOBUFS) => size *= 2,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 /// Gets a subset of the password file entry for the current effective uid (not the real uid). The
 /// populated data includes the username, euid, gid, shell, and home directory. On non-Windows
 /// systems, all data comes from getpwuid_r(3). On Windows, uid, gid, and shell are all set to
@@ -101,17 +128,27 @@ pub fn get_passwd() -> crate::Result<User> {
 
 /// Returns the hostname
 pub fn gethostname() -> crate::Result<String> {
-    let mut size = UV_MAXHOSTNAMESIZE as u64;
-    let mut buf: Vec<std::os::raw::c_uchar> = Vec::with_capacity(size as _);
-    crate::uvret(unsafe { uv_os_gethostname(buf.as_mut_ptr() as _, &mut size as _) }).map(|_| {
-        // size is the length of the string, *not* including the null
-        unsafe { buf.set_len((size as usize) + 1) };
-        unsafe { CStr::from_bytes_with_nul_unchecked(&buf) }
-            .to_string_lossy()
-            .into_owned()
+    get_string(UV_MAXHOSTNAMESIZE as _, |buf, size| unsafe {
+        uv_os_gethostname(buf, size as _)
     })
 }
 
+/// Gets the current user's home directory. On Windows, homedir() first checks the USERPROFILE
+/// environment variable using GetEnvironmentVariableW(). If USERPROFILE is not set,
+/// GetUserProfileDirectoryW() is called. On all other operating systems, homedir() first checks
+/// the HOME environment variable using getenv(3). If HOME is not set, getpwuid_r(3) is called.
+pub fn homedir() -> crate::Result<String> {
+    get_string(256, |buf, size| unsafe { uv_os_homedir(buf, size as _) })
+}
+
+/// Gets the temp directory. On Windows, tmpdir() uses GetTempPathW(). On all other operating
+/// systems, tmpdir() uses the first environment variable found in the ordered list TMPDIR, TMP,
+/// TEMP, and TEMPDIR. If none of these are found, the path "/tmp" is used, or, on Android,
+/// "/data/local/tmp" is used. The returned path has no trailing slash.
+pub fn tmpdir() -> crate::Result<String> {
+    get_string(256, |buf, size| unsafe { uv_os_tmpdir(buf, size as _) })
+}
+
 /// Returns the current process ID.
 pub fn getpid() -> Pid {
     unsafe { uv_os_getpid() as _ }
@@ -143,7 +180,12 @@ pub fn getpriority(pid: Pid) -> crate::Result<i32> {
 ///
 /// Note: On Windows, setting PRIORITY_HIGHEST will only work for elevated user, for others it will
 /// be silently reduced to PRIORITY_HIGH.
+///
+/// Returns EINVAL if priority is outside of the -20..=19 range.
 pub fn setpriority(pid: Pid, priority: i32) -> crate::Result<()> {
+    if !(-20..=19).contains(&priority) {
+        return Err(crate::Error::EINVAL);
+    }
     crate::uvret(unsafe { uv_os_setpriority(pid as _, priority as _) })
 }
 
@@ -155,3 +197,69 @@ pub fn uname() -> crate::Result<SystemInfo> {
     crate::uvret(unsafe { uv_os_uname(&mut buf as _) })?;
     Ok(buf.into_inner())
 }
+
+/// Retrieves all environment variables and collects them into a HashMap. On Windows, the
+/// environment is read as wide characters and converted to UTF-8 by libuv.
+pub fn os_environ_map() -> crate::Result<HashMap<String, String>> {
+    let mut items: *mut uv_env_item_t = std::ptr::null_mut();
+    let mut count: std::os::raw::c_int = 0;
+    crate::uvret(unsafe { uv_os_environ(&mut items as _, &mut count as _) })?;
+
+    let result = unsafe { std::slice::from_raw_parts(items, count as _) }
+        .iter()
+        .map(|item| unsafe {
+            (
+                CStr::from_ptr(item.name).to_string_lossy().into_owned(),
+                CStr::from_ptr(item.value).to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+    unsafe { uv_os_free_environ(items, count) };
+    Ok(result)
+}
+
+/// Retrieves the value of the environment variable name, ignoring ASCII case when comparing
+/// names. If the environment contains several variables that differ only by case, an exact match
+/// is preferred; otherwise the one whose name sorts first (by byte value) is returned, so the
+/// result doesn't depend on the order of the environment. Returns None if the variable does not
+/// exist.
+///
+/// This is useful for configuration that must behave the same on Windows, where environment
+/// variable names are case-insensitive, and other platforms, where they are not.
+pub fn os_getenv_ci(name: &str) -> crate::Result<Option<String>> {
+    let mut env = os_environ_map()?;
+    if let Some(value) = env.remove(name) {
+        return Ok(Some(value));
+    }
+    Ok(env
+        .into_iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case(name))
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, v)| v))
+}
+
+/// Retrieves the value of the environment variable name. Returns ENOENT if the variable does not
+/// exist. On Windows, the value is converted from wide characters to UTF-8.
+pub fn getenv(name: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let name = CString::new(name)?;
+    get_string(256, |buf, size| unsafe {
+        uv_os_getenv(name.as_ptr(), buf, size as _)
+    })
+    .map_err(|e| Box::new(e) as _)
+}
+
+/// Creates or updates the environment variable name with value. On Windows, name and value are
+/// converted to wide characters.
+pub fn setenv(name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let name = CString::new(name)?;
+    let value = CString::new(value)?;
+    crate::uvret(unsafe { uv_os_setenv(name.as_ptr(), value.as_ptr()) })
+        .map_err(|e| Box::new(e) as _)
+}
+
+/// Deletes the environment variable specified by name. If no such environment variable exists,
+/// this function returns successfully.
+pub fn unsetenv(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let name = CString::new(name)?;
+    crate::uvret(unsafe { uv_os_unsetenv(name.as_ptr()) }).map_err(|e| Box::new(e) as _)
+}
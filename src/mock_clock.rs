@@ -0,0 +1,239 @@
+//! A deterministic, manually-advanced clock for testing timer logic. This module is only available
+//! with the `mock-clock` feature enabled.
+//!
+//! libuv reads the time from the operating system and caches it at the start of each loop
+//! iteration; there is no way to make it use a fake clock. Instead, MockTimer mirrors the API of
+//! TimerHandle, but is driven by a MockClock that only moves forward when you call advance(). Code
+//! that needs to be tested deterministically can be written against a small trait of your own
+//! that is implemented for both TimerHandle and MockTimer.
+//!
+//! Limitations:
+//!   * Only MockTimers are affected by the MockClock. Loop::now(), TimerHandles and any other
+//!     time-related functionality in libuv continue to use the real clock.
+//!   * MockTimer callbacks are called synchronously from within MockClock::advance(), not from
+//!     the event loop. They are never called while the loop is running unless advance() is called
+//!     from a loop callback.
+//!   * MockTimers are not handles: they cannot be walked, ref'd, unref'd or closed, and they do
+//!     not keep a loop alive.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+callbacks! {
+    pub MockTimerCB(timer: MockTimer);
+}
+
+struct TimerState {
+    active: bool,
+    started: bool,
+    due: u64,
+    repeat: u64,
+
+    /// Used to break ties between timers that are due at the same time: timers fire in the order
+    /// they were scheduled, just like libuv.
+    seq: u64,
+    cb: MockTimerCB<'static>,
+}
+
+struct ClockState {
+    now: u64,
+    next_seq: u64,
+    timers: Vec<TimerState>,
+}
+
+/// A manually-advanced clock that drives MockTimers. Time is measured in milliseconds, starting at
+/// zero. Cloning a MockClock returns another reference to the same clock.
+#[derive(Clone)]
+pub struct MockClock {
+    state: Rc<RefCell<ClockState>>,
+}
+
+impl MockClock {
+    /// Create a new clock with the time set to zero.
+    pub fn new() -> MockClock {
+        MockClock {
+            state: Rc::new(RefCell::new(ClockState {
+                now: 0,
+                next_seq: 0,
+                timers: Vec::new(),
+            })),
+        }
+    }
+
+    /// Returns the current time of the clock, in milliseconds.
+    pub fn now(&self) -> u64 {
+        self.state.borrow().now
+    }
+
+    /// Advance the clock by the given number of milliseconds, calling the callbacks of any timers
+    /// that become due, in order. The clock's time is set to each timer's due time before its
+    /// callback is called. Repeating timers may fire several times during a single call.
+    ///
+    /// Calling advance(0) fires any timers that are already due, such as timers that were started
+    /// with a timeout of zero.
+    ///
+    /// A repeating timer whose next due time would be after u64::MAX milliseconds is stopped
+    /// instead of being rescheduled.
+    pub fn advance(&self, ms: u64) {
+        let target = self.now().saturating_add(ms);
+        loop {
+            // find the next timer that is due
+            let next = {
+                let state = self.state.borrow();
+                state
+                    .timers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| t.active && t.due <= target)
+                    .min_by_key(|(_, t)| (t.due, t.seq))
+                    .map(|(id, _)| id)
+            };
+            let id = match next {
+                Some(id) => id,
+                None => break,
+            };
+
+            // reschedule (or stop) the timer, and take its callback so that the callback is free
+            // to call methods on the timer and clock
+            let mut cb = {
+                let mut state = self.state.borrow_mut();
+                let seq = state.next_seq;
+                state.next_seq += 1;
+
+                let due = state.timers[id].due;
+                state.now = due;
+
+                // a repeating timer whose next due time is past the end of time stops, rather
+                // than firing forever at u64::MAX
+                let timer = &mut state.timers[id];
+                match due.checked_add(timer.repeat) {
+                    Some(next) if timer.repeat > 0 => {
+                        timer.due = next;
+                        timer.seq = seq;
+                    }
+                    _ => timer.active = false,
+                }
+                std::mem::replace(&mut timer.cb, MockTimerCB::Nil)
+            };
+
+            cb.call(MockTimer {
+                clock: self.clone(),
+                id,
+            });
+
+            // put the callback back, unless it was replaced while it was running
+            let mut state = self.state.borrow_mut();
+            let timer = &mut state.timers[id];
+            if timer.cb.is_nil() {
+                timer.cb = cb;
+            }
+        }
+
+        self.state.borrow_mut().now = target;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        MockClock::new()
+    }
+}
+
+/// A timer driven by a MockClock. The API mirrors TimerHandle; see the module documentation for
+/// the differences.
+#[derive(Clone)]
+pub struct MockTimer {
+    clock: MockClock,
+    id: usize,
+}
+
+impl MockTimer {
+    /// Create a new timer driven by the given clock.
+    pub fn new(clock: &MockClock) -> MockTimer {
+        let mut state = clock.state.borrow_mut();
+        state.timers.push(TimerState {
+            active: false,
+            started: false,
+            due: 0,
+            repeat: 0,
+            seq: 0,
+            cb: MockTimerCB::Nil,
+        });
+        MockTimer {
+            clock: clock.clone(),
+            id: state.timers.len() - 1,
+        }
+    }
+
+    /// Start the timer. timeout and repeat are in milliseconds.
+    ///
+    /// If timeout is zero, the callback fires the next time the clock is advanced. If repeat is
+    /// non-zero, the callback fires first after timeout milliseconds and then repeatedly after
+    /// repeat milliseconds.
+    ///
+    /// If the timer is already active, it is simply updated.
+    pub fn start<CB: Into<MockTimerCB<'static>>>(&mut self, timeout: u64, repeat: u64, cb: CB) {
+        let mut state = self.clock.state.borrow_mut();
+        let now = state.now;
+        let seq = state.next_seq;
+        state.next_seq += 1;
+
+        let timer = &mut state.timers[self.id];
+        timer.active = true;
+        timer.started = true;
+        timer.due = now.saturating_add(timeout);
+        timer.repeat = repeat;
+        timer.seq = seq;
+        timer.cb = cb.into();
+    }
+
+    /// Stop the timer, the callback will not be called anymore.
+    pub fn stop(&mut self) {
+        self.clock.state.borrow_mut().timers[self.id].active = false;
+    }
+
+    /// Stop the timer, and if it is repeating restart it using the repeat value as the timeout. If
+    /// the timer has never been started before it returns EINVAL.
+    pub fn again(&mut self) -> crate::Result<()> {
+        let mut state = self.clock.state.borrow_mut();
+        let now = state.now;
+        let seq = state.next_seq;
+        state.next_seq += 1;
+
+        let timer = &mut state.timers[self.id];
+        if !timer.started {
+            return Err(crate::Error::EINVAL);
+        }
+        timer.active = timer.repeat > 0;
+        timer.due = now.saturating_add(timer.repeat);
+        timer.seq = seq;
+        Ok(())
+    }
+
+    /// Set the repeat interval value in milliseconds. As with TimerHandle, setting the repeat
+    /// value from the timer's callback does not immediately take effect.
+    pub fn set_repeat(&mut self, repeat: u64) {
+        self.clock.state.borrow_mut().timers[self.id].repeat = repeat;
+    }
+
+    /// Get the timer repeat value.
+    pub fn get_repeat(&self) -> u64 {
+        self.clock.state.borrow().timers[self.id].repeat
+    }
+
+    /// Get the timer due value or 0 if it has expired. The time is relative to the clock's now().
+    pub fn get_due_in(&self) -> u64 {
+        let state = self.clock.state.borrow();
+        let timer = &state.timers[self.id];
+        if timer.active {
+            timer.due.saturating_sub(state.now)
+        } else {
+            0
+        }
+    }
+
+    /// Returns true if the timer has been started and has not yet expired or been stopped.
+    pub fn is_active(&self) -> bool {
+        self.clock.state.borrow().timers[self.id].active
+    }
+}
@@ -6,7 +6,8 @@ use uv::{
     uv_interface_addresses, UV_IF_NAMESIZE,
 };
 
-/// Data type for interface addresses.
+/// Data type for interface addresses. physical_address is the MAC address, which is all zeros for
+/// interfaces that don't have one (such as loopback).
 pub struct InterfaceAddress {
     pub name: String,
     pub physical_address: [u8; 6],
@@ -81,16 +82,22 @@ pub fn if_indexto_iid(ifindex: u32) -> crate::Result<String> {
     )
 }
 
-/// Gets address information about the network interfaces on the system.
+/// Gets address information about the network interfaces on the system. An interface with
+/// several addresses is listed once per address. Everything is copied out of libuv's array before
+/// it is freed.
 pub fn interface_addresses() -> Result<Vec<InterfaceAddress>, Box<dyn std::error::Error>> {
-    let mut addresses: *mut uv::uv_interface_address_t = unsafe { std::mem::zeroed() };
+    let mut addresses: *mut uv::uv_interface_address_t = std::ptr::null_mut();
     let mut count: std::os::raw::c_int = 0;
     crate::uvret(unsafe { uv_interface_addresses(&mut addresses as _, &mut count as _) })?;
 
-    let result = unsafe { std::slice::from_raw_parts(addresses, count as _) }
-        .iter()
-        .map(|addr| addr.try_into_inner())
-        .collect();
+    let result = if addresses.is_null() {
+        Ok(Vec::new())
+    } else {
+        unsafe { std::slice::from_raw_parts(addresses, count as _) }
+            .iter()
+            .map(|addr| addr.try_into_inner())
+            .collect()
+    };
     unsafe { uv_free_interface_addresses(addresses, count as _) };
     result
 }
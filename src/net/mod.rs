@@ -3,3 +3,6 @@ pub use addrinfo::*;
 
 pub mod misc;
 pub use misc::*;
+
+pub mod resolver_cache;
+pub use resolver_cache::*;
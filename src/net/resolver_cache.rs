@@ -0,0 +1,156 @@
+use crate::{FromInner, IntoInner};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::rc::Rc;
+
+type Key = (String, u16);
+
+struct CacheEntry {
+    addrs: Vec<SocketAddr>,
+    expires: u64,
+}
+
+struct CacheState {
+    ttl: u64,
+    entries: HashMap<Key, CacheEntry>,
+
+    /// Callbacks waiting on a lookup that is currently running
+    in_flight: HashMap<Key, Vec<crate::ResolveCB<'static>>>,
+}
+
+/// A cache over Loop::resolve(). Successful lookups are cached for a fixed time-to-live, and
+/// concurrent lookups of the same host and port are coalesced: if ten lookups for the same host
+/// are made while the first is still running, only one libuv request is made and all ten
+/// callbacks receive its result. Failed lookups are not cached.
+///
+/// Expiry is measured with Loop::now(), so it has the same millisecond granularity and is only
+/// updated once per loop iteration.
+///
+/// The cache does not hold on to a loop: the loop is passed to each call to resolve() instead, so
+/// the cache can never outlive it. A ResolverCache should always be used with the same loop,
+/// since expiry times from different loops are not comparable, and only from that loop's thread.
+/// It does no locking. Cloning a ResolverCache returns another reference to the same cache.
+#[derive(Clone)]
+pub struct ResolverCache {
+    state: Rc<RefCell<CacheState>>,
+}
+
+impl ResolverCache {
+    /// Create a new, empty cache. Results are cached for ttl milliseconds.
+    pub fn new(ttl: u64) -> ResolverCache {
+        ResolverCache {
+            state: Rc::new(RefCell::new(CacheState {
+                ttl,
+                entries: HashMap::new(),
+                in_flight: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Resolve host and port to a list of socket addresses, as Loop::resolve() does.
+    ///
+    /// If an unexpired result is cached, the callback is called immediately, before this function
+    /// returns. If a lookup for the same host and port is already running, the callback is called
+    /// when it completes. Otherwise, a new lookup is started. If starting the lookup fails, the
+    /// error is returned and the callback is not called.
+    pub fn resolve<CB: Into<crate::ResolveCB<'static>>>(
+        &self,
+        r#loop: &crate::Loop,
+        host: &str,
+        port: u16,
+        cb: CB,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut cb = cb.into();
+        let key = (host.to_owned(), port);
+
+        let cached = {
+            let mut state = self.state.borrow_mut();
+            let now = r#loop.now();
+            let cached = state
+                .entries
+                .get(&key)
+                .filter(|entry| now < entry.expires)
+                .map(|entry| entry.addrs.clone());
+            if cached.is_none() {
+                state.entries.remove(&key);
+            }
+            cached
+        };
+        if let Some(addrs) = cached {
+            cb.call(Ok(addrs));
+            return Ok(());
+        }
+
+        {
+            let mut state = self.state.borrow_mut();
+            if let Some(waiting) = state.in_flight.get_mut(&key) {
+                waiting.push(cb);
+                return Ok(());
+            }
+            state.in_flight.insert(key.clone(), vec![cb]);
+        }
+
+        let state = self.state.clone();
+        // the lookup completes while the loop is running, so the loop is still alive then
+        let loop_ptr: *mut uv::uv_loop_t = r#loop.into_inner();
+        let req_key = key.clone();
+        let result = r#loop.resolve(host, port, move |addrs: crate::Result<Vec<SocketAddr>>| {
+            // release the borrow before calling any callbacks, which may use the cache
+            let waiting = {
+                let mut state = state.borrow_mut();
+                if let Ok(addrs) = &addrs {
+                    let now = crate::Loop::from_inner(loop_ptr).now();
+                    let expires = now.saturating_add(state.ttl);
+                    state.entries.insert(
+                        req_key.clone(),
+                        CacheEntry {
+                            addrs: addrs.clone(),
+                            expires,
+                        },
+                    );
+                }
+                state.in_flight.remove(&req_key).unwrap_or_default()
+            };
+            for mut cb in waiting {
+                cb.call(addrs.clone());
+            }
+        });
+        if let Err(e) = result {
+            self.state.borrow_mut().in_flight.remove(&key);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Remove the cached result for host and port, if any. The next resolve() will start a new
+    /// lookup. This does not affect lookups that are already running.
+    pub fn invalidate(&self, host: &str, port: u16) {
+        self.state
+            .borrow_mut()
+            .entries
+            .remove(&(host.to_owned(), port));
+    }
+
+    /// Remove all cached results. This does not affect lookups that are already running.
+    pub fn clear(&self) {
+        self.state.borrow_mut().entries.clear();
+    }
+
+    /// Returns the number of cached results, including any that have expired but have not been
+    /// removed yet.
+    pub fn len(&self) -> usize {
+        self.state.borrow().entries.len()
+    }
+
+    /// Returns true if there are no cached results.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of lookups that are currently running. Concurrent lookups of the same
+    /// host and port count once.
+    pub fn pending_lookups(&self) -> usize {
+        self.state.borrow().in_flight.len()
+    }
+}
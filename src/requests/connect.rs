@@ -32,9 +32,12 @@ pub(crate) extern "C" fn uv_connect_cb(req: *mut uv_connect_t, status: std::os::
 }
 
 /// Connect request type
-#[derive(Clone, Copy)]
 pub struct ConnectReq {
     req: *mut uv_connect_t,
+
+    /// Set once the request has been handed to libuv, which then destroys it after the callback
+    /// runs. A request that was never submitted is destroyed when it is dropped.
+    submitted: bool,
 }
 
 impl ConnectReq {
@@ -52,7 +55,10 @@ impl ConnectReq {
             super::ConnectData(ConnectDataFields { connect_cb }),
         );
 
-        Ok(ConnectReq { req })
+        Ok(ConnectReq {
+            req,
+            submitted: false,
+        })
     }
 
     /// The stream where this connection request is running
@@ -61,16 +67,35 @@ impl ConnectReq {
     }
 
     pub fn destroy(&mut self) {
-        crate::Req::free_data(uv_handle!(self.req));
+        if !self.req.is_null() {
+            crate::Req::free_data(uv_handle!(self.req));
 
-        let layout = std::alloc::Layout::new::<uv_connect_t>();
-        unsafe { std::alloc::dealloc(self.req as _, layout) };
+            let layout = std::alloc::Layout::new::<uv_connect_t>();
+            unsafe { std::alloc::dealloc(self.req as _, layout) };
+            self.req = std::ptr::null_mut();
+        }
+    }
+
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+}
+
+impl Drop for ConnectReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
     }
 }
 
 impl FromInner<*mut uv_connect_t> for ConnectReq {
     fn from_inner(req: *mut uv_connect_t) -> ConnectReq {
-        ConnectReq { req }
+        ConnectReq {
+            req,
+            submitted: true,
+        }
     }
 }
 
@@ -88,7 +113,10 @@ impl Inner<*mut uv::uv_req_t> for ConnectReq {
 
 impl From<ConnectReq> for crate::Req {
     fn from(connect: ConnectReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&connect))
+        let req = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&connect));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(connect);
+        req
     }
 }
 
@@ -1,5 +1,7 @@
 use crate::{FromInner, Inner, IntoInner};
+use std::cell::Cell;
 use std::ffi::CStr;
+use std::rc::Rc;
 use uv::{
     uv_fs_get_path, uv_fs_get_ptr, uv_fs_get_result, uv_fs_get_statbuf, uv_fs_get_system_error,
     uv_fs_get_type, uv_fs_req_cleanup, uv_fs_t,
@@ -31,16 +33,21 @@ pub(crate) extern "C" fn uv_fs_cb(req: *mut uv_fs_t) {
 }
 
 /// File system request type.
-#[derive(Clone, Copy)]
 pub struct FsReq {
     req: *mut uv_fs_t,
+
+    /// Set once the request has been handed to libuv with a callback, after which it is destroyed
+    /// once the callback runs. Requests that were never submitted, or that ran synchronously
+    /// (without a callback), are destroyed when they are dropped.
+    submitted: bool,
 }
 
 impl FsReq {
     /// Create a new fs request
     pub fn new<CB: Into<FsCB<'static>>>(cb: CB) -> crate::Result<FsReq> {
         let layout = std::alloc::Layout::new::<uv_fs_t>();
-        let req = unsafe { std::alloc::alloc(layout) as *mut uv_fs_t };
+        // zeroed, so that uv_fs_req_cleanup() finds nothing to free if the request never ran
+        let req = unsafe { std::alloc::alloc_zeroed(layout) as *mut uv_fs_t };
         if req.is_null() {
             return Err(crate::Error::ENOMEM);
         }
@@ -48,7 +55,10 @@ impl FsReq {
         let fs_cb = cb.into();
         crate::Req::initialize_data(uv_handle!(req), super::FsData(FsDataFields { fs_cb }));
 
-        Ok(FsReq { req })
+        Ok(FsReq {
+            req,
+            submitted: false,
+        })
     }
 
     /// Type of request that was made
@@ -123,6 +133,47 @@ impl FsReq {
         }
     }
 
+    /// Run the loop until this request completes, then return its result(). The callback is still
+    /// called. This is a synchronous adapter for scripts, tests and CLI tools that issue a single
+    /// operation and don't want to manage the loop themselves. Other handles and requests on the
+    /// loop continue to be serviced while waiting.
+    ///
+    /// The request must have been returned by one of the async fs_* functions and must not have
+    /// completed yet. If the request was made without a callback, the operation has already run
+    /// synchronously, so its result is returned immediately and the request is destroy()'d.
+    /// Returns EINVAL if the loop runs out of work before the request completes.
+    ///
+    /// Warning: this runs the loop, so it must not be called from within a callback - run() is not
+    /// reentrant.
+    pub fn wait(mut self, r#loop: &mut crate::Loop) -> crate::Result<usize> {
+        if unsafe { (*self.req).cb }.is_none() {
+            let result = self.result();
+            self.destroy();
+            return result;
+        }
+
+        let result = Rc::new(Cell::new(None));
+        let dataptr = crate::Req::get_data(uv_handle!(self.req));
+        if dataptr.is_null() {
+            return Err(crate::Error::EINVAL);
+        }
+        unsafe {
+            if let super::FsData(d) = &mut *dataptr {
+                let mut fs_cb = std::mem::replace(&mut d.fs_cb, FsCB::Nil);
+                let result = result.clone();
+                d.fs_cb = (move |req: FsReq| {
+                    // grab the result first, in case the callback destroys the request
+                    result.set(Some(req.result()));
+                    fs_cb.call(req);
+                })
+                .into();
+            }
+        }
+
+        r#loop.run_until(|| result.get().is_some())?;
+        result.get().unwrap_or(Err(crate::Error::EINVAL))
+    }
+
     /// Free up memory associated with this request. If you are using one of the async fs_*
     /// functions, this will be called automatically after the callback runs.
     pub fn destroy(&mut self) {
@@ -135,11 +186,28 @@ impl FsReq {
             self.req = std::ptr::null_mut();
         }
     }
+
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it. Must
+    /// not be called for synchronous requests, which libuv never destroys.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+}
+
+impl Drop for FsReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
+    }
 }
 
 impl FromInner<*mut uv_fs_t> for FsReq {
     fn from_inner(req: *mut uv_fs_t) -> FsReq {
-        FsReq { req }
+        FsReq {
+            req,
+            submitted: true,
+        }
     }
 }
 
@@ -157,7 +225,10 @@ impl Inner<*mut uv::uv_req_t> for FsReq {
 
 impl From<FsReq> for crate::Req {
     fn from(fs: FsReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&fs))
+        let req = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&fs));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(fs);
+        req
     }
 }
 
@@ -1,6 +1,7 @@
 use crate::{FromInner, Inner, IntoInner};
 use std::ffi::CString;
-use uv::{addrinfo, uv_freeaddrinfo, uv_getaddrinfo, uv_getaddrinfo_t};
+use std::net::SocketAddr;
+use uv::{addrinfo, uv_freeaddrinfo, uv_getaddrinfo, uv_getaddrinfo_t, AF_UNSPEC, SOCK_STREAM};
 
 callbacks! {
     pub GetAddrInfoCB(
@@ -8,6 +9,7 @@ callbacks! {
         status: crate::Result<u32>,
         res: Vec<crate::AddrInfo>
     );
+    pub ResolveCB(addrs: crate::Result<Vec<SocketAddr>>);
 }
 
 /// Additional data stored on the request
@@ -35,21 +37,24 @@ extern "C" fn uv_getaddrinfo_cb(req: *mut uv_getaddrinfo_t, status: i32, res: *m
     // free memory
     let mut req = GetAddrInfoReq::from_inner(req);
     req.destroy();
-
-    unsafe { uv_freeaddrinfo(res) };
 }
 
 /// GetAddrInfo request type
-#[derive(Clone, Copy)]
 pub struct GetAddrInfoReq {
     req: *mut uv_getaddrinfo_t,
+
+    /// Set once the request has been handed to libuv with a callback, after which it is destroyed
+    /// once the callback runs. Requests that were never submitted, or that ran synchronously
+    /// (without a callback), are destroyed when they are dropped.
+    submitted: bool,
 }
 
 impl GetAddrInfoReq {
     /// Create a new GetAddrInfo request
     pub fn new<CB: Into<GetAddrInfoCB<'static>>>(cb: CB) -> crate::Result<GetAddrInfoReq> {
         let layout = std::alloc::Layout::new::<uv_getaddrinfo_t>();
-        let req = unsafe { std::alloc::alloc(layout) as *mut uv_getaddrinfo_t };
+        // zeroed, so that destroy() finds no addrinfo to free if the request never ran
+        let req = unsafe { std::alloc::alloc_zeroed(layout) as *mut uv_getaddrinfo_t };
         if req.is_null() {
             return Err(crate::Error::ENOMEM);
         }
@@ -60,14 +65,16 @@ impl GetAddrInfoReq {
             super::GetAddrInfoData(GetAddrInfoDataFields { cb }),
         );
 
-        Ok(GetAddrInfoReq { req })
+        Ok(GetAddrInfoReq {
+            req,
+            submitted: false,
+        })
     }
 
     /// Frees memory associated with this request
     pub fn destroy(&mut self) {
         if !self.req.is_null() {
-            // do I need this?
-            // unsafe { uv_freeaddrinfo((*self.req).addrinfo) };
+            unsafe { uv_freeaddrinfo((*self.req).addrinfo) };
             crate::Req::free_data(uv_handle!(self.req));
 
             let layout = std::alloc::Layout::new::<uv_getaddrinfo_t>();
@@ -76,6 +83,12 @@ impl GetAddrInfoReq {
         }
     }
 
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it. Must
+    /// not be called for synchronous requests, which libuv never destroys.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+
     /// Retrieve an iterator of AddrInfo responses
     pub fn addrinfos(self) -> Vec<crate::AddrInfo> {
         let ai = unsafe { (*self.req).addrinfo };
@@ -88,9 +101,20 @@ impl GetAddrInfoReq {
     }
 }
 
+impl Drop for GetAddrInfoReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
+    }
+}
+
 impl FromInner<*mut uv_getaddrinfo_t> for GetAddrInfoReq {
     fn from_inner(req: *mut uv_getaddrinfo_t) -> GetAddrInfoReq {
-        GetAddrInfoReq { req }
+        GetAddrInfoReq {
+            req,
+            submitted: true,
+        }
     }
 }
 
@@ -108,7 +132,10 @@ impl Inner<*mut uv::uv_req_t> for GetAddrInfoReq {
 
 impl From<GetAddrInfoReq> for crate::Req {
     fn from(req: GetAddrInfoReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&req))
+        let r = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&req));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(req);
+        r
     }
 }
 
@@ -160,6 +187,8 @@ impl crate::Loop {
         .map_err(|e| Box::new(e) as _);
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -194,7 +223,63 @@ impl crate::Loop {
         service: Option<&str>,
         hints: Option<crate::AddrInfo>,
     ) -> Result<Vec<crate::AddrInfo>, Box<dyn std::error::Error>> {
-        self._getaddrinfo(node, service, hints, ())
-            .map(|req| req.addrinfos())
+        self._getaddrinfo(node, service, hints, ()).map(|mut req| {
+            let ai = unsafe { (*req.req).addrinfo };
+            let res: Vec<crate::AddrInfo> = ai.into_inner();
+            req.destroy();
+            res
+        })
+    }
+
+    /// Resolve host to a list of addresses using the given port. This is a convenience wrapper
+    /// around getaddrinfo() for the common case of "give me addresses I can connect to": the port
+    /// is passed as the service, and the lookup is restricted to stream sockets (both IPv4 and
+    /// IPv6). The addresses passed to the callback can be used directly with TcpHandle::connect().
+    pub fn resolve<CB: Into<ResolveCB<'static>>>(
+        &self,
+        host: &str,
+        port: u16,
+        cb: CB,
+    ) -> Result<GetAddrInfoReq, Box<dyn std::error::Error>> {
+        let hints = crate::AddrInfo {
+            flags: 0,
+            family: AF_UNSPEC as _,
+            socktype: SOCK_STREAM as _,
+            protocol: 0,
+            canonical_name: None,
+            addr: None,
+        };
+        let mut cb = cb.into();
+        self._getaddrinfo(
+            Some(host),
+            Some(&port.to_string()),
+            Some(hints),
+            move |_: GetAddrInfoReq, status: crate::Result<u32>, res: Vec<crate::AddrInfo>| {
+                cb.call(status.map(|_| res.into_iter().filter_map(|ai| ai.addr).collect()))
+            },
+        )
+    }
+
+    /// Resolve host to a list of addresses using the given port, returning a future that resolves
+    /// once the lookup has completed. This is the async counterpart to resolve().
+    ///
+    /// Dropping the future before it resolves does not cancel the lookup: libuv will still
+    /// complete it, but the result will be discarded.
+    ///
+    /// Note: the future must be polled on the thread that is running the loop, and the loop must
+    /// be run for the future to resolve.
+    #[cfg(feature = "futures")]
+    pub fn resolve_async(&self, host: &str, port: u16) -> crate::ResolveFuture {
+        let (completer, future) = crate::futures::channel();
+        let mut completer = Some(completer);
+        let cb = move |addrs: crate::Result<Vec<SocketAddr>>| {
+            if let Some(completer) = completer.take() {
+                completer.send(addrs.map_err(|e| Box::new(e) as _));
+            }
+        };
+        match self.resolve(host, port, cb) {
+            Ok(_) => future,
+            Err(e) => crate::ReqFuture::ready(Err(e)),
+        }
     }
 }
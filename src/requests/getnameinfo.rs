@@ -3,6 +3,41 @@ use std::ffi::CStr;
 use std::net::SocketAddr;
 use uv::{uv_getnameinfo, uv_getnameinfo_t};
 
+#[cfg(not(windows))]
+use libc::{NI_DGRAM, NI_NAMEREQD, NI_NOFQDN, NI_NUMERICHOST, NI_NUMERICSERV};
+
+// ws2def.h
+#[cfg(windows)]
+const NI_NOFQDN: i32 = 0x01;
+#[cfg(windows)]
+const NI_NUMERICHOST: i32 = 0x02;
+#[cfg(windows)]
+const NI_NAMEREQD: i32 = 0x04;
+#[cfg(windows)]
+const NI_NUMERICSERV: i32 = 0x08;
+#[cfg(windows)]
+const NI_DGRAM: i32 = 0x10;
+
+bitflags! {
+    /// Flags to getnameinfo(). Consult man -s 3 getnameinfo for more details.
+    pub struct NameInfoFlags: u32 {
+        /// Return only the hostname part of the FQDN for local hosts
+        const NOFQDN = NI_NOFQDN as _;
+
+        /// Return the numeric form of the host address instead of its name
+        const NUMERICHOST = NI_NUMERICHOST as _;
+
+        /// Fail with an error if the host's name cannot be determined
+        const NAMEREQD = NI_NAMEREQD as _;
+
+        /// Return the numeric form of the service address instead of its name
+        const NUMERICSERV = NI_NUMERICSERV as _;
+
+        /// The service is datagram (UDP) based rather than stream (TCP) based
+        const DGRAM = NI_DGRAM as _;
+    }
+}
+
 callbacks! {
     pub GetNameInfoCB(
         req: GetNameInfoReq,
@@ -28,8 +63,17 @@ extern "C" fn uv_getnameinfo_cb(
     if !dataptr.is_null() {
         unsafe {
             if let super::GetNameInfoData(d) = &mut *dataptr {
-                let hostname = CStr::from_ptr(hostname).to_string_lossy().into_owned();
-                let service = CStr::from_ptr(service).to_string_lossy().into_owned();
+                // libuv passes NULL for both on failure
+                let hostname = if hostname.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(hostname).to_string_lossy().into_owned()
+                };
+                let service = if service.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(service).to_string_lossy().into_owned()
+                };
                 let status = if status < 0 {
                     Err(crate::Error::from_inner(status as uv::uv_errno_t))
                 } else {
@@ -46,9 +90,13 @@ extern "C" fn uv_getnameinfo_cb(
 }
 
 /// GetNameInfo request type
-#[derive(Clone, Copy)]
 pub struct GetNameInfoReq {
     req: *mut uv_getnameinfo_t,
+
+    /// Set once the request has been handed to libuv with a callback, after which it is destroyed
+    /// once the callback runs. Requests that were never submitted, or that ran synchronously
+    /// (without a callback), are destroyed when they are dropped.
+    submitted: bool,
 }
 
 impl GetNameInfoReq {
@@ -66,7 +114,10 @@ impl GetNameInfoReq {
             super::GetNameInfoData(GetNameInfoDataFields { cb }),
         );
 
-        Ok(GetNameInfoReq { req })
+        Ok(GetNameInfoReq {
+            req,
+            submitted: false,
+        })
     }
 
     /// Loop that started this getnameinfo request and where completion will be reported.
@@ -105,11 +156,28 @@ impl GetNameInfoReq {
             self.req = std::ptr::null_mut();
         }
     }
+
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it. Must
+    /// not be called for synchronous requests, which libuv never destroys.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+}
+
+impl Drop for GetNameInfoReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
+    }
 }
 
 impl FromInner<*mut uv_getnameinfo_t> for GetNameInfoReq {
     fn from_inner(req: *mut uv_getnameinfo_t) -> GetNameInfoReq {
-        GetNameInfoReq { req }
+        GetNameInfoReq {
+            req,
+            submitted: true,
+        }
     }
 }
 
@@ -127,7 +195,10 @@ impl Inner<*mut uv::uv_req_t> for GetNameInfoReq {
 
 impl From<GetNameInfoReq> for crate::Req {
     fn from(req: GetNameInfoReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&req))
+        let r = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&req));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(req);
+        r
     }
 }
 
@@ -144,11 +215,10 @@ impl crate::Loop {
     fn _getnameinfo<CB: Into<GetNameInfoCB<'static>>>(
         &self,
         addr: &SocketAddr,
-        flags: u32,
+        flags: NameInfoFlags,
         cb: CB,
     ) -> Result<GetNameInfoReq, Box<dyn std::error::Error>> {
-        let mut sockaddr: uv::sockaddr = unsafe { std::mem::zeroed() };
-        crate::fill_sockaddr(&mut sockaddr, addr)?;
+        let sockaddr = crate::to_sockaddr(addr)?;
 
         let cb = cb.into();
         let uv_cb = use_c_callback!(uv_getnameinfo_cb, cb);
@@ -158,12 +228,14 @@ impl crate::Loop {
                 self.into_inner(),
                 req.inner(),
                 uv_cb,
-                &sockaddr as _,
-                flags as _,
+                uv_handle!(&sockaddr),
+                flags.bits() as _,
             )
         });
         if result.is_err() {
             req.destroy();
+        } else if uv_cb.is_some() {
+            req.set_submitted();
         }
         result.map(|_| req).map_err(|e| Box::new(e) as _)
     }
@@ -173,11 +245,11 @@ impl crate::Loop {
     /// If successful, the callback will get called sometime in the future with the lookup result.
     /// Consult man -s 3 getnameinfo for more details.
     ///
-    /// flags is the bitwise OR of NI_* constants
+    /// If the lookup fails, the hostname and service passed to the callback are empty.
     pub fn getnameinfo<CB: Into<GetNameInfoCB<'static>>>(
         &self,
         addr: &SocketAddr,
-        flags: u32,
+        flags: NameInfoFlags,
         cb: CB,
     ) -> Result<GetNameInfoReq, Box<dyn std::error::Error>> {
         self._getnameinfo(addr, flags, cb)
@@ -186,12 +258,10 @@ impl crate::Loop {
     /// Synchronous getnameinfo(3).
     ///
     /// If successful, will return a tuple of (host, service) Strings.
-    ///
-    /// flags is the bitwise OR of NI_* constants
     pub fn getnameinfo_sync(
         &self,
         addr: &SocketAddr,
-        flags: u32,
+        flags: NameInfoFlags,
     ) -> Result<(String, String), Box<dyn std::error::Error>> {
         self._getnameinfo(addr, flags, ()).map(|mut req| {
             let res = (req.host(), req.service());
@@ -42,9 +42,12 @@ extern "C" fn uv_random_cb(
 }
 
 /// Random data request type.
-#[derive(Clone, Copy)]
 pub struct RandomReq {
     req: *mut uv_random_t,
+
+    /// Set once the request has been handed to libuv, which then destroys it after the callback
+    /// runs. A request that was never submitted is destroyed when it is dropped.
+    submitted: bool,
 }
 
 impl RandomReq {
@@ -62,22 +65,44 @@ impl RandomReq {
             super::RandomData(RandomDataFields { random_cb }),
         );
 
-        Ok(RandomReq { req })
+        Ok(RandomReq {
+            req,
+            submitted: false,
+        })
     }
 
     /// Free memory - this will be called automatically after the callback (if using the async
     /// function), or before returning the random data (if using the sync version)
     pub fn destroy(&mut self) {
-        crate::Req::free_data(uv_handle!(self.req));
+        if !self.req.is_null() {
+            crate::Req::free_data(uv_handle!(self.req));
 
-        let layout = std::alloc::Layout::new::<uv_random_t>();
-        unsafe { std::alloc::dealloc(self.req as _, layout) };
+            let layout = std::alloc::Layout::new::<uv_random_t>();
+            unsafe { std::alloc::dealloc(self.req as _, layout) };
+            self.req = std::ptr::null_mut();
+        }
+    }
+
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+}
+
+impl Drop for RandomReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
     }
 }
 
 impl FromInner<*mut uv_random_t> for RandomReq {
     fn from_inner(req: *mut uv_random_t) -> RandomReq {
-        RandomReq { req }
+        RandomReq {
+            req,
+            submitted: true,
+        }
     }
 }
 
@@ -95,7 +120,10 @@ impl Inner<*mut uv::uv_req_t> for RandomReq {
 
 impl From<RandomReq> for crate::Req {
     fn from(random: RandomReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&random))
+        let req = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&random));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(random);
+        req
     }
 }
 
@@ -151,6 +179,8 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
@@ -24,6 +24,14 @@ impl std::fmt::Display for ReqType {
 }
 
 /// Req is the base type for all libuv requests
+///
+/// A request that is submitted to libuv (by Loop::queue_work(), StreamTrait::write(), etc) is
+/// destroyed automatically after its callback is called, or immediately if the submission fails.
+/// A request that is created with new() but never submitted is destroyed when it is dropped.
+///
+/// Req itself is a lightweight, Copy wrapper around a pointer, so it never frees the request.
+/// Converting an unsubmitted request into a Req hands it over without destroying it, so it will
+/// leak.
 #[derive(Clone, Copy)]
 pub struct Req {
     req: *mut uv_req_t,
@@ -72,7 +80,8 @@ impl ToReq for Req {
 }
 
 pub trait ReqTrait: ToReq {
-    /// Cancel a pending request. Fails if the request is executing or has finished executing.
+    /// Cancel a pending request. Fails with EBUSY if the request is executing or has finished
+    /// executing, and with EINVAL if the request type cannot be cancelled.
     ///
     /// Only cancellation of FsReq, GetAddrInfoReq, GetNameInfoReq, RandomReq and WorkReq requests
     /// is currently supported.
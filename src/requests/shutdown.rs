@@ -32,9 +32,12 @@ pub(crate) extern "C" fn uv_shutdown_cb(req: *mut uv_shutdown_t, status: std::os
 }
 
 /// Shutdown request type.
-#[derive(Clone, Copy)]
 pub struct ShutdownReq {
     req: *mut uv_shutdown_t,
+
+    /// Set once the request has been handed to libuv, which then destroys it after the callback
+    /// runs. A request that was never submitted is destroyed when it is dropped.
+    submitted: bool,
 }
 
 impl ShutdownReq {
@@ -52,7 +55,10 @@ impl ShutdownReq {
             super::ShutdownData(ShutdownDataFields { shutdown_cb }),
         );
 
-        Ok(ShutdownReq { req })
+        Ok(ShutdownReq {
+            req,
+            submitted: false,
+        })
     }
 
     /// The stream where this connection request is running
@@ -62,16 +68,35 @@ impl ShutdownReq {
 
     /// Deallocate the shutdown request - called automatically in the shudown callback
     pub fn destroy(&mut self) {
-        crate::Req::free_data(uv_handle!(self.req));
+        if !self.req.is_null() {
+            crate::Req::free_data(uv_handle!(self.req));
 
-        let layout = std::alloc::Layout::new::<uv_shutdown_t>();
-        unsafe { std::alloc::dealloc(self.req as _, layout) };
+            let layout = std::alloc::Layout::new::<uv_shutdown_t>();
+            unsafe { std::alloc::dealloc(self.req as _, layout) };
+            self.req = std::ptr::null_mut();
+        }
+    }
+
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+}
+
+impl Drop for ShutdownReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
     }
 }
 
 impl FromInner<*mut uv_shutdown_t> for ShutdownReq {
     fn from_inner(req: *mut uv_shutdown_t) -> ShutdownReq {
-        ShutdownReq { req }
+        ShutdownReq {
+            req,
+            submitted: true,
+        }
     }
 }
 
@@ -89,7 +114,10 @@ impl Inner<*mut uv::uv_req_t> for ShutdownReq {
 
 impl From<ShutdownReq> for crate::Req {
     fn from(shutdown: ShutdownReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&shutdown))
+        let req = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&shutdown));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(shutdown);
+        req
     }
 }
 
@@ -35,13 +35,20 @@ pub(crate) extern "C" fn uv_udp_send_cb(req: *mut uv_udp_send_t, status: std::os
 }
 
 /// UDP Send request type
-#[derive(Clone, Copy)]
 pub struct UdpSendReq {
     req: *mut uv_udp_send_t,
 
+    /// Set once the request has been handed to libuv, which then destroys it after the callback
+    /// runs. A request that was never submitted is destroyed when it is dropped.
+    submitted: bool,
+
     /// This is only guaranteed to be set if the UdpSendReq was created by new(). If it was created
     /// any other way (such as by From<*mut uv_udp_send_t>), it will not be set.
     pub(crate) bufs_ptr: *const uv::uv_buf_t,
+
+    /// The number of uv_buf_t's pointed to by bufs_ptr. Like bufs_ptr, this is only guaranteed to
+    /// be set if the UdpSendReq was created by new().
+    pub(crate) bufs_len: usize,
 }
 
 impl UdpSendReq {
@@ -49,14 +56,38 @@ impl UdpSendReq {
     pub fn new<CB: Into<UdpSendCB<'static>>>(
         bufs: &[impl crate::BufTrait],
         cb: CB,
+    ) -> crate::Result<UdpSendReq> {
+        let (bufs_ptr, bufs_len, bufs_capacity) = bufs.into_inner();
+        UdpSendReq::new_from_raw_parts(bufs_ptr, bufs_len, bufs_capacity, cb)
+    }
+
+    /// Create a new udp send request from an owned Vec of uv_buf_t's. The Vec is destroyed along
+    /// with the request, but the memory the uv_buf_t's point to is not.
+    pub(crate) fn new_with_uv_bufs<CB: Into<UdpSendCB<'static>>>(
+        bufs: Vec<uv::uv_buf_t>,
+        cb: CB,
+    ) -> crate::Result<UdpSendReq> {
+        let mut bufs = std::mem::ManuallyDrop::new(bufs);
+        let bufs_ptr = bufs.as_mut_ptr();
+        let bufs_len = bufs.len();
+        let bufs_capacity = bufs.capacity();
+        UdpSendReq::new_from_raw_parts(bufs_ptr, bufs_len, bufs_capacity, cb)
+    }
+
+    fn new_from_raw_parts<CB: Into<UdpSendCB<'static>>>(
+        bufs_ptr: *mut uv::uv_buf_t,
+        bufs_len: usize,
+        bufs_capacity: usize,
+        cb: CB,
     ) -> crate::Result<UdpSendReq> {
         let layout = std::alloc::Layout::new::<uv_udp_send_t>();
         let req = unsafe { std::alloc::alloc(layout) as *mut uv_udp_send_t };
         if req.is_null() {
+            // reclaim the Vec<uv_buf_t> so it isn't leaked
+            unsafe { std::mem::drop(Vec::from_raw_parts(bufs_ptr, bufs_len, bufs_capacity)) };
             return Err(crate::Error::ENOMEM);
         }
 
-        let (bufs_ptr, bufs_len, bufs_capacity) = bufs.into_inner();
         let udp_send_cb = cb.into();
         crate::Req::initialize_data(
             uv_handle!(req),
@@ -68,7 +99,12 @@ impl UdpSendReq {
             }),
         );
 
-        Ok(UdpSendReq { req, bufs_ptr })
+        Ok(UdpSendReq {
+            req,
+            submitted: false,
+            bufs_ptr,
+            bufs_len,
+        })
     }
 
     /// UDP handle where this send request is taking place.
@@ -77,23 +113,43 @@ impl UdpSendReq {
     }
 
     pub fn destroy(&mut self) {
-        let dataptr = crate::Req::get_data(uv_handle!(self.req));
-        if !dataptr.is_null() {
-            if let super::UdpSendData(d) = unsafe { &mut *dataptr } {
-                if !d.bufs_ptr.is_null() {
-                    // This will destroy the Vec<uv_buf_t>, but will not actually deallocate the
-                    // uv_buf_t's themselves. That's up to the user to do.
-                    unsafe {
-                        std::mem::drop(Vec::from_raw_parts(d.bufs_ptr, d.bufs_len, d.bufs_capacity))
-                    };
+        if !self.req.is_null() {
+            let dataptr = crate::Req::get_data(uv_handle!(self.req));
+            if !dataptr.is_null() {
+                if let super::UdpSendData(d) = unsafe { &mut *dataptr } {
+                    if !d.bufs_ptr.is_null() {
+                        // This will destroy the Vec<uv_buf_t>, but will not actually deallocate the
+                        // uv_buf_t's themselves. That's up to the user to do.
+                        unsafe {
+                            std::mem::drop(Vec::from_raw_parts(
+                                d.bufs_ptr,
+                                d.bufs_len,
+                                d.bufs_capacity,
+                            ))
+                        };
+                    }
                 }
             }
+
+            crate::Req::free_data(uv_handle!(self.req));
+
+            let layout = std::alloc::Layout::new::<uv_udp_send_t>();
+            unsafe { std::alloc::dealloc(self.req as _, layout) };
+            self.req = std::ptr::null_mut();
         }
+    }
 
-        crate::Req::free_data(uv_handle!(self.req));
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+}
 
-        let layout = std::alloc::Layout::new::<uv_udp_send_t>();
-        unsafe { std::alloc::dealloc(self.req as _, layout) }
+impl Drop for UdpSendReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
     }
 }
 
@@ -101,7 +157,9 @@ impl FromInner<*mut uv_udp_send_t> for UdpSendReq {
     fn from_inner(req: *mut uv_udp_send_t) -> UdpSendReq {
         UdpSendReq {
             req,
+            submitted: true,
             bufs_ptr: std::ptr::null(),
+            bufs_len: 0,
         }
     }
 }
@@ -120,7 +178,10 @@ impl Inner<*mut uv::uv_req_t> for UdpSendReq {
 
 impl From<UdpSendReq> for crate::Req {
     fn from(udp_send: UdpSendReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&udp_send))
+        let req = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&udp_send));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(udp_send);
+        req
     }
 }
 
@@ -1,4 +1,6 @@
 use crate::{FromInner, Inner, IntoInner};
+use std::cell::Cell;
+use std::rc::Rc;
 use uv::{uv_queue_work, uv_work_t};
 
 callbacks! {
@@ -45,9 +47,12 @@ extern "C" fn uv_after_work_cb(req: *mut uv_work_t, status: i32) {
 }
 
 /// Work request type.
-#[derive(Clone, Copy)]
 pub struct WorkReq {
     req: *mut uv_work_t,
+
+    /// Set once the request has been handed to libuv, which then destroys it after the callback
+    /// runs. A request that was never submitted is destroyed when it is dropped.
+    submitted: bool,
 }
 
 impl WorkReq {
@@ -72,7 +77,10 @@ impl WorkReq {
             }),
         );
 
-        Ok(WorkReq { req })
+        Ok(WorkReq {
+            req,
+            submitted: false,
+        })
     }
 
     /// Loop that started this request and where completion will be reported
@@ -80,17 +88,71 @@ impl WorkReq {
         unsafe { (*self.req).loop_ }.into_inner()
     }
 
+    /// Run the loop until this request completes, then return the status that was passed to the
+    /// after_work_cb (which is still called). This is a synchronous adapter for scripts, tests and
+    /// CLI tools that issue a single operation and don't want to manage the loop themselves. Other
+    /// handles and requests on the loop continue to be serviced while waiting.
+    ///
+    /// The request must have been returned by Loop::queue_work() and must not have completed yet.
+    /// Returns EINVAL if the loop runs out of work before the request completes.
+    ///
+    /// Warning: this runs the loop, so it must not be called from within a callback - run() is not
+    /// reentrant.
+    pub fn wait(self, r#loop: &mut crate::Loop) -> crate::Result<u32> {
+        let result = Rc::new(Cell::new(None));
+        let dataptr = crate::Req::get_data(uv_handle!(self.req));
+        if dataptr.is_null() {
+            return Err(crate::Error::EINVAL);
+        }
+        unsafe {
+            if let super::WorkData(d) = &mut *dataptr {
+                let mut after_work_cb = std::mem::replace(&mut d.after_work_cb, AfterWorkCB::Nil);
+                let result = result.clone();
+                d.after_work_cb = (move |req: WorkReq, status: crate::Result<u32>| {
+                    result.set(Some(status));
+                    after_work_cb.call(req, status);
+                })
+                .into();
+            }
+        }
+
+        r#loop.run_until(|| result.get().is_some())?;
+        result.get().unwrap_or(Err(crate::Error::EINVAL))
+    }
+
+    /// Free up memory associated with this request. This is called automatically after the
+    /// after_work_cb runs (or if queue_work() fails), and when a request that was never submitted
+    /// is dropped, so it rarely needs to be called directly.
     pub fn destroy(&mut self) {
-        crate::Req::free_data(uv_handle!(self.req));
+        if !self.req.is_null() {
+            crate::Req::free_data(uv_handle!(self.req));
 
-        let layout = std::alloc::Layout::new::<uv_work_t>();
-        unsafe { std::alloc::dealloc(self.req as _, layout) };
+            let layout = std::alloc::Layout::new::<uv_work_t>();
+            unsafe { std::alloc::dealloc(self.req as _, layout) };
+            self.req = std::ptr::null_mut();
+        }
+    }
+
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+}
+
+impl Drop for WorkReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
     }
 }
 
 impl FromInner<*mut uv_work_t> for WorkReq {
     fn from_inner(req: *mut uv_work_t) -> WorkReq {
-        WorkReq { req }
+        WorkReq {
+            req,
+            submitted: true,
+        }
     }
 }
 
@@ -108,7 +170,10 @@ impl Inner<*mut uv::uv_req_t> for WorkReq {
 
 impl From<WorkReq> for crate::Req {
     fn from(work: WorkReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&work))
+        let req = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&work));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(work);
+        req
     }
 }
 
@@ -139,7 +204,33 @@ impl crate::Loop {
         });
         if result.is_err() {
             req.destroy();
+        } else {
+            req.set_submitted();
         }
         result.map(|_| req)
     }
+
+    /// Run work_cb in a thread from the threadpool, returning a future that resolves once work_cb
+    /// has completed. This is the async counterpart to queue_work(). The future resolves to
+    /// ECANCELED if the request is cancelled.
+    ///
+    /// Dropping the future before it resolves does not cancel the work: libuv will still run it,
+    /// but the result will be discarded.
+    ///
+    /// Note: the future must be polled on the thread that is running the loop, and the loop must
+    /// be run for the future to resolve.
+    #[cfg(feature = "futures")]
+    pub fn queue_work_async<CB: Into<WorkCB<'static>>>(&self, work_cb: CB) -> crate::WorkFuture {
+        let (completer, future) = crate::futures::channel();
+        let mut completer = Some(completer);
+        let after_work_cb = move |_: WorkReq, status: crate::Result<u32>| {
+            if let Some(completer) = completer.take() {
+                completer.send(status);
+            }
+        };
+        match self.queue_work(work_cb, after_work_cb) {
+            Ok(_) => future,
+            Err(e) => crate::ReqFuture::ready(Err(e)),
+        }
+    }
 }
@@ -15,6 +15,17 @@ pub(crate) struct WriteDataFields<'a> {
 
 /// Callback for uv_write/uv_write2
 pub(crate) extern "C" fn uv_write_cb(req: *mut uv_write_t, status: std::os::raw::c_int) {
+    // the write is no longer pending
+    let stream = unsafe { (*req).handle };
+    if !stream.is_null() {
+        let streamptr = crate::StreamHandle::get_data(stream);
+        if !streamptr.is_null() {
+            unsafe {
+                (*streamptr).pending_writes = (*streamptr).pending_writes.saturating_sub(1);
+            }
+        }
+    }
+
     let dataptr = crate::Req::get_data(uv_handle!(req));
     if !dataptr.is_null() {
         unsafe {
@@ -38,28 +49,71 @@ pub(crate) extern "C" fn uv_write_cb(req: *mut uv_write_t, status: std::os::raw:
 /// stream is in non-blocking mode, write requests sent with StreamHandle::write will be queued.
 /// Reusing objects at this point is undefined behaviour. It is safe to reuse the WriteReq object
 /// only after the callback passed to StreamHandle::write is fired.
-#[derive(Clone, Copy)]
 pub struct WriteReq {
     req: *mut uv_write_t,
 
+    /// Set once the request has been handed to libuv, which then destroys it after the callback
+    /// runs. A request that was never submitted is destroyed when it is dropped.
+    submitted: bool,
+
     /// This is only guaranteed to be set if the WriteReq was created by new(). If it was created
     /// any other way (such as by From<*mut uv_write_t>), it will not be set.
     pub(crate) bufs_ptr: *const uv::uv_buf_t,
+
+    /// The number of uv_buf_t's pointed to by bufs_ptr. Like bufs_ptr, this is only guaranteed to
+    /// be set if the WriteReq was created by new().
+    pub(crate) bufs_len: usize,
 }
 
 impl WriteReq {
-    /// Create a new write request
+    /// Create a new write request. If bufs is empty, the request will contain a single empty
+    /// buffer since libuv requires at least one buffer per write, so the request still results in
+    /// a (zero byte) write to the stream. See StreamTrait::write() for details on zero-length
+    /// writes.
     pub fn new<CB: Into<WriteCB<'static>>>(
         bufs: &[impl crate::BufTrait],
         cb: CB,
+    ) -> crate::Result<WriteReq> {
+        if bufs.is_empty() {
+            return WriteReq::new_with_uv_bufs(Vec::new(), cb);
+        }
+
+        let (bufs_ptr, bufs_len, bufs_capacity) = bufs.into_inner();
+        WriteReq::new_from_raw_parts(bufs_ptr, bufs_len, bufs_capacity, cb)
+    }
+
+    /// Create a new write request from an owned Vec of uv_buf_t's. The Vec is destroyed along with
+    /// the request, but the memory the uv_buf_t's point to is not. As with new(), an empty Vec
+    /// results in a single empty buffer.
+    pub(crate) fn new_with_uv_bufs<CB: Into<WriteCB<'static>>>(
+        mut bufs: Vec<uv::uv_buf_t>,
+        cb: CB,
+    ) -> crate::Result<WriteReq> {
+        if bufs.is_empty() {
+            bufs.push(unsafe { uv::uv_buf_init(std::ptr::null_mut(), 0) });
+        }
+
+        let mut bufs = std::mem::ManuallyDrop::new(bufs);
+        let bufs_ptr = bufs.as_mut_ptr();
+        let bufs_len = bufs.len();
+        let bufs_capacity = bufs.capacity();
+        WriteReq::new_from_raw_parts(bufs_ptr, bufs_len, bufs_capacity, cb)
+    }
+
+    fn new_from_raw_parts<CB: Into<WriteCB<'static>>>(
+        bufs_ptr: *mut uv::uv_buf_t,
+        bufs_len: usize,
+        bufs_capacity: usize,
+        cb: CB,
     ) -> crate::Result<WriteReq> {
         let layout = std::alloc::Layout::new::<uv_write_t>();
         let req = unsafe { std::alloc::alloc(layout) as *mut uv_write_t };
         if req.is_null() {
+            // reclaim the Vec<uv_buf_t> so it isn't leaked
+            unsafe { std::mem::drop(Vec::from_raw_parts(bufs_ptr, bufs_len, bufs_capacity)) };
             return Err(crate::Error::ENOMEM);
         }
 
-        let (bufs_ptr, bufs_len, bufs_capacity) = bufs.into_inner();
         let write_cb = cb.into();
         crate::Req::initialize_data(
             uv_handle!(req),
@@ -71,7 +125,12 @@ impl WriteReq {
             }),
         );
 
-        Ok(WriteReq { req, bufs_ptr })
+        Ok(WriteReq {
+            req,
+            submitted: false,
+            bufs_ptr,
+            bufs_len,
+        })
     }
 
     /// The stream where this connection request is running
@@ -86,23 +145,43 @@ impl WriteReq {
 
     /// Deallocate the WriteReq - this is done automatically in the write callback.
     pub fn destroy(&mut self) {
-        let dataptr = crate::Req::get_data(uv_handle!(self.req));
-        if !dataptr.is_null() {
-            if let super::WriteData(d) = unsafe { &mut *dataptr } {
-                if !d.bufs_ptr.is_null() {
-                    // This will destroy the Vec<uv_buf_t>, but will not actually deallocate the
-                    // uv_buf_t's themselves. That's up to the user to do.
-                    unsafe {
-                        std::mem::drop(Vec::from_raw_parts(d.bufs_ptr, d.bufs_len, d.bufs_capacity))
-                    };
+        if !self.req.is_null() {
+            let dataptr = crate::Req::get_data(uv_handle!(self.req));
+            if !dataptr.is_null() {
+                if let super::WriteData(d) = unsafe { &mut *dataptr } {
+                    if !d.bufs_ptr.is_null() {
+                        // This will destroy the Vec<uv_buf_t>, but will not actually deallocate the
+                        // uv_buf_t's themselves. That's up to the user to do.
+                        unsafe {
+                            std::mem::drop(Vec::from_raw_parts(
+                                d.bufs_ptr,
+                                d.bufs_len,
+                                d.bufs_capacity,
+                            ))
+                        };
+                    }
                 }
             }
+
+            crate::Req::free_data(uv_handle!(self.req));
+
+            let layout = std::alloc::Layout::new::<uv_write_t>();
+            unsafe { std::alloc::dealloc(self.req as _, layout) };
+            self.req = std::ptr::null_mut();
         }
+    }
 
-        crate::Req::free_data(uv_handle!(self.req));
+    /// Marks the request as submitted to libuv, which is then responsible for destroying it.
+    pub(crate) fn set_submitted(&mut self) {
+        self.submitted = true;
+    }
+}
 
-        let layout = std::alloc::Layout::new::<uv_write_t>();
-        unsafe { std::alloc::dealloc(self.req as _, layout) };
+impl Drop for WriteReq {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.destroy();
+        }
     }
 }
 
@@ -110,7 +189,9 @@ impl FromInner<*mut uv_write_t> for WriteReq {
     fn from_inner(req: *mut uv_write_t) -> WriteReq {
         WriteReq {
             req,
+            submitted: true,
             bufs_ptr: std::ptr::null(),
+            bufs_len: 0,
         }
     }
 }
@@ -129,7 +210,10 @@ impl Inner<*mut uv::uv_req_t> for WriteReq {
 
 impl From<WriteReq> for crate::Req {
     fn from(write: WriteReq) -> crate::Req {
-        crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&write))
+        let req = crate::Req::from_inner(Inner::<*mut uv::uv_req_t>::inner(&write));
+        // hand the request over to the Req without destroying it
+        std::mem::forget(write);
+        req
     }
 }
 
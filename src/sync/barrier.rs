@@ -0,0 +1,53 @@
+use crate::Inner;
+use uv::{uv_barrier_destroy, uv_barrier_init, uv_barrier_t, uv_barrier_wait};
+
+/// A barrier backed by uv_barrier_t, which blocks threads until a given number of them have all
+/// reached it.
+pub struct Barrier {
+    barrier: *mut uv_barrier_t,
+}
+
+unsafe impl Send for Barrier {}
+unsafe impl Sync for Barrier {}
+
+impl Barrier {
+    /// Create a new barrier that releases waiting threads once count threads have called wait().
+    pub fn new(count: u32) -> crate::Result<Barrier> {
+        let layout = std::alloc::Layout::new::<uv_barrier_t>();
+        let barrier = unsafe { std::alloc::alloc(layout) as *mut uv_barrier_t };
+        if barrier.is_null() {
+            return Err(crate::Error::ENOMEM);
+        }
+
+        let result = crate::uvret(unsafe { uv_barrier_init(barrier, count as _) });
+        if result.is_err() {
+            unsafe { std::alloc::dealloc(barrier as _, layout) };
+        }
+        result.map(|_| Barrier { barrier })
+    }
+
+    /// Block the current thread until count threads have called wait(). Exactly one of the
+    /// released threads gets true as the return value; this "serializer" thread can be used to do
+    /// any cleanup that must happen once all of the threads have reached the barrier.
+    pub fn wait(&self) -> bool {
+        unsafe { uv_barrier_wait(self.barrier) > 0 }
+    }
+}
+
+impl Drop for Barrier {
+    fn drop(&mut self) {
+        if !self.barrier.is_null() {
+            unsafe { uv_barrier_destroy(self.barrier) };
+
+            let layout = std::alloc::Layout::new::<uv_barrier_t>();
+            unsafe { std::alloc::dealloc(self.barrier as _, layout) };
+            self.barrier = std::ptr::null_mut();
+        }
+    }
+}
+
+impl Inner<*mut uv_barrier_t> for Barrier {
+    fn inner(&self) -> *mut uv_barrier_t {
+        self.barrier
+    }
+}
@@ -0,0 +1,77 @@
+use crate::Inner;
+use uv::{
+    uv_cond_broadcast, uv_cond_destroy, uv_cond_init, uv_cond_signal, uv_cond_t, uv_cond_timedwait,
+    uv_cond_wait, uv_mutex_t,
+};
+
+/// A condition variable backed by uv_cond_t. Waiting requires holding the lock on a Mutex, which
+/// is atomically released while waiting and re-acquired before returning.
+///
+/// Note: Like pthread condition variables, waits may wake up spuriously, so callers should check
+/// their condition in a loop.
+pub struct Cond {
+    cond: *mut uv_cond_t,
+}
+
+unsafe impl Send for Cond {}
+unsafe impl Sync for Cond {}
+
+impl Cond {
+    /// Create a new condition variable.
+    pub fn new() -> crate::Result<Cond> {
+        let layout = std::alloc::Layout::new::<uv_cond_t>();
+        let cond = unsafe { std::alloc::alloc(layout) as *mut uv_cond_t };
+        if cond.is_null() {
+            return Err(crate::Error::ENOMEM);
+        }
+
+        let result = crate::uvret(unsafe { uv_cond_init(cond) });
+        if result.is_err() {
+            unsafe { std::alloc::dealloc(cond as _, layout) };
+        }
+        result.map(|_| Cond { cond })
+    }
+
+    /// Wake up one thread waiting on this condition variable.
+    pub fn signal(&self) {
+        unsafe { uv_cond_signal(self.cond) };
+    }
+
+    /// Wake up all threads waiting on this condition variable.
+    pub fn broadcast(&self) {
+        unsafe { uv_cond_broadcast(self.cond) };
+    }
+
+    /// Block the current thread until this condition variable is signaled. guard is the lock on
+    /// the mutex protecting the condition; it is released while waiting.
+    pub fn wait(&self, guard: &mut crate::MutexGuard<'_>) {
+        let mutex: *mut uv_mutex_t = guard.mutex().inner();
+        unsafe { uv_cond_wait(self.cond, mutex) };
+    }
+
+    /// Like wait(), but gives up after timeout nanoseconds. Returns false if the timeout expired
+    /// before the condition variable was signaled.
+    pub fn timedwait(&self, guard: &mut crate::MutexGuard<'_>, timeout: u64) -> bool {
+        let mutex: *mut uv_mutex_t = guard.mutex().inner();
+        // the only error uv_cond_timedwait() returns is ETIMEDOUT
+        crate::uvret(unsafe { uv_cond_timedwait(self.cond, mutex, timeout) }).is_ok()
+    }
+}
+
+impl Drop for Cond {
+    fn drop(&mut self) {
+        if !self.cond.is_null() {
+            unsafe { uv_cond_destroy(self.cond) };
+
+            let layout = std::alloc::Layout::new::<uv_cond_t>();
+            unsafe { std::alloc::dealloc(self.cond as _, layout) };
+            self.cond = std::ptr::null_mut();
+        }
+    }
+}
+
+impl Inner<*mut uv_cond_t> for Cond {
+    fn inner(&self) -> *mut uv_cond_t {
+        self.cond
+    }
+}
@@ -0,0 +1,27 @@
+//! Thread synchronization primitives. These are thin wrappers around the threading and
+//! synchronization utilities provided by libuv, whose semantics closely mirror the pthreads API.
+//! The libuv primitives must not be moved once initialized, so each type owns a heap allocation.
+
+pub mod mutex;
+pub use mutex::*;
+
+pub mod rwlock;
+pub use rwlock::*;
+
+pub mod semaphore;
+pub use semaphore::*;
+
+pub mod cond;
+pub use cond::*;
+
+pub mod barrier;
+pub use barrier::*;
+
+pub mod thread;
+pub use thread::*;
+
+pub mod tls;
+pub use tls::*;
+
+pub mod once;
+pub use once::*;
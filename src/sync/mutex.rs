@@ -0,0 +1,108 @@
+use crate::Inner;
+use std::marker::PhantomData;
+use uv::{
+    uv_mutex_destroy, uv_mutex_init, uv_mutex_init_recursive, uv_mutex_lock, uv_mutex_t,
+    uv_mutex_trylock, uv_mutex_unlock,
+};
+
+/// A mutual exclusion primitive backed by uv_mutex_t. Unlike std::sync::Mutex, this does not
+/// protect any data itself: it is useful for coordinating with code that is already built around
+/// libuv's threading primitives, such as work scheduled with queue_work().
+///
+/// The lock is released when the MutexGuard returned by lock() or try_lock() is dropped.
+pub struct Mutex {
+    mutex: *mut uv_mutex_t,
+}
+
+unsafe impl Send for Mutex {}
+unsafe impl Sync for Mutex {}
+
+impl Mutex {
+    /// Allocate and initialize a mutex with the given init function.
+    fn new_with(init: unsafe extern "C" fn(*mut uv_mutex_t) -> i32) -> crate::Result<Mutex> {
+        let layout = std::alloc::Layout::new::<uv_mutex_t>();
+        let mutex = unsafe { std::alloc::alloc(layout) as *mut uv_mutex_t };
+        if mutex.is_null() {
+            return Err(crate::Error::ENOMEM);
+        }
+
+        let result = crate::uvret(unsafe { init(mutex) });
+        if result.is_err() {
+            unsafe { std::alloc::dealloc(mutex as _, layout) };
+        }
+        result.map(|_| Mutex { mutex })
+    }
+
+    /// Create a new mutex.
+    pub fn new() -> crate::Result<Mutex> {
+        Mutex::new_with(uv_mutex_init)
+    }
+
+    /// Create a new recursive mutex, which may be locked again by the thread that already holds
+    /// it. It must be unlocked (that is, each guard dropped) as many times as it was locked.
+    pub fn new_recursive() -> crate::Result<Mutex> {
+        Mutex::new_with(uv_mutex_init_recursive)
+    }
+
+    /// Acquire the lock, blocking the current thread until it is available.
+    ///
+    /// Warning: locking a non-recursive mutex that is already held by the current thread will
+    /// deadlock or abort, depending on the platform.
+    pub fn lock(&self) -> MutexGuard<'_> {
+        unsafe { uv_mutex_lock(self.mutex) };
+        MutexGuard {
+            mutex: self,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Attempt to acquire the lock without blocking. Returns EBUSY (or EAGAIN) if the lock is held
+    /// by another thread.
+    pub fn try_lock(&self) -> crate::Result<MutexGuard<'_>> {
+        crate::uvret(unsafe { uv_mutex_trylock(self.mutex) }).map(|_| MutexGuard {
+            mutex: self,
+            _not_send: PhantomData,
+        })
+    }
+}
+
+impl Drop for Mutex {
+    fn drop(&mut self) {
+        if !self.mutex.is_null() {
+            unsafe { uv_mutex_destroy(self.mutex) };
+
+            let layout = std::alloc::Layout::new::<uv_mutex_t>();
+            unsafe { std::alloc::dealloc(self.mutex as _, layout) };
+            self.mutex = std::ptr::null_mut();
+        }
+    }
+}
+
+impl Inner<*mut uv_mutex_t> for Mutex {
+    fn inner(&self) -> *mut uv_mutex_t {
+        self.mutex
+    }
+}
+
+/// A held lock on a Mutex. The lock is released when the guard is dropped. The guard cannot be
+/// sent to another thread, since the lock must be released by the thread that acquired it.
+pub struct MutexGuard<'a> {
+    mutex: &'a Mutex,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl MutexGuard<'_> {
+    /// Release the lock. This is equivalent to dropping the guard.
+    pub fn unlock(self) {}
+
+    /// The mutex that this guard has locked.
+    pub fn mutex(&self) -> &Mutex {
+        self.mutex
+    }
+}
+
+impl Drop for MutexGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { uv_mutex_unlock(self.mutex.mutex) };
+    }
+}
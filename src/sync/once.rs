@@ -0,0 +1,74 @@
+use std::cell::{Cell, UnsafeCell};
+use std::ffi::c_void;
+use uv::{uv_once, uv_once_t};
+
+#[cfg(unix)]
+const ONCE_INIT: uv_once_t = unsafe { std::mem::transmute(libc::PTHREAD_ONCE_INIT) };
+
+#[cfg(windows)]
+const ONCE_INIT: uv_once_t = uv_once_t {
+    ran: 0,
+    event: std::ptr::null_mut(),
+};
+
+thread_local! {
+    // uv_once() doesn't pass any arguments to its callback, so the closure from call_once() is
+    // passed through a thread local instead. The callback runs on the calling thread.
+    static ONCE_FN: Cell<*mut c_void> = Cell::new(std::ptr::null_mut());
+}
+
+/// Callback for uv_once
+extern "C" fn uv_once_cb() {
+    let ptr = ONCE_FN.with(|f| f.get());
+    if !ptr.is_null() {
+        let f = unsafe { &mut *(ptr as *mut &mut dyn FnMut()) };
+        f();
+    }
+}
+
+/// One-time initialization backed by uv_once_t: the first call to call_once() runs its closure,
+/// and every other call, on any thread, blocks until that has finished and then returns without
+/// running its own closure.
+///
+/// uv_once_t must be statically initialized, which is why new() is a const fn: a Once is meant to
+/// be stored in a static, such as `static INIT: Once = Once::new();`.
+///
+/// Warning: a Once must not be moved after call_once() has been called. If the closure panics,
+/// the process is aborted.
+pub struct Once {
+    once: UnsafeCell<uv_once_t>,
+}
+
+unsafe impl Send for Once {}
+unsafe impl Sync for Once {}
+
+impl Once {
+    /// Create a new Once, equivalent to UV_ONCE_INIT.
+    pub const fn new() -> Once {
+        Once {
+            once: UnsafeCell::new(ONCE_INIT),
+        }
+    }
+
+    /// Run f if no closure has been run by this Once yet. If another thread is currently running
+    /// a closure, block until it has finished.
+    pub fn call_once<F: FnOnce()>(&self, f: F) {
+        let mut f = Some(f);
+        let mut call = || {
+            if let Some(f) = f.take() {
+                f();
+            }
+        };
+        let mut call: &mut dyn FnMut() = &mut call;
+
+        let previous = ONCE_FN.with(|cell| cell.replace(&mut call as *mut &mut dyn FnMut() as _));
+        unsafe { uv_once(self.once.get(), Some(uv_once_cb)) };
+        ONCE_FN.with(|cell| cell.set(previous));
+    }
+}
+
+impl Default for Once {
+    fn default() -> Once {
+        Once::new()
+    }
+}
@@ -0,0 +1,123 @@
+use crate::Inner;
+use std::marker::PhantomData;
+use uv::{
+    uv_rwlock_destroy, uv_rwlock_init, uv_rwlock_rdlock, uv_rwlock_rdunlock, uv_rwlock_t,
+    uv_rwlock_tryrdlock, uv_rwlock_trywrlock, uv_rwlock_wrlock, uv_rwlock_wrunlock,
+};
+
+/// A reader-writer lock backed by uv_rwlock_t. Any number of readers may hold the lock at the
+/// same time, but a writer has exclusive access. Like Mutex, this does not protect any data
+/// itself.
+///
+/// The lock is released when the ReadGuard or WriteGuard is dropped.
+pub struct RwLock {
+    rwlock: *mut uv_rwlock_t,
+}
+
+unsafe impl Send for RwLock {}
+unsafe impl Sync for RwLock {}
+
+impl RwLock {
+    /// Create a new reader-writer lock.
+    pub fn new() -> crate::Result<RwLock> {
+        let layout = std::alloc::Layout::new::<uv_rwlock_t>();
+        let rwlock = unsafe { std::alloc::alloc(layout) as *mut uv_rwlock_t };
+        if rwlock.is_null() {
+            return Err(crate::Error::ENOMEM);
+        }
+
+        let result = crate::uvret(unsafe { uv_rwlock_init(rwlock) });
+        if result.is_err() {
+            unsafe { std::alloc::dealloc(rwlock as _, layout) };
+        }
+        result.map(|_| RwLock { rwlock })
+    }
+
+    /// Acquire a shared read lock, blocking the current thread until it is available.
+    pub fn rdlock(&self) -> ReadGuard<'_> {
+        unsafe { uv_rwlock_rdlock(self.rwlock) };
+        ReadGuard {
+            rwlock: self,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Attempt to acquire a shared read lock without blocking. Returns EBUSY (or EAGAIN) if a
+    /// writer holds the lock.
+    pub fn tryrdlock(&self) -> crate::Result<ReadGuard<'_>> {
+        crate::uvret(unsafe { uv_rwlock_tryrdlock(self.rwlock) }).map(|_| ReadGuard {
+            rwlock: self,
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Acquire an exclusive write lock, blocking the current thread until it is available.
+    pub fn wrlock(&self) -> WriteGuard<'_> {
+        unsafe { uv_rwlock_wrlock(self.rwlock) };
+        WriteGuard {
+            rwlock: self,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Attempt to acquire an exclusive write lock without blocking. Returns EBUSY (or EAGAIN) if
+    /// the lock is held by a reader or another writer.
+    pub fn trywrlock(&self) -> crate::Result<WriteGuard<'_>> {
+        crate::uvret(unsafe { uv_rwlock_trywrlock(self.rwlock) }).map(|_| WriteGuard {
+            rwlock: self,
+            _not_send: PhantomData,
+        })
+    }
+}
+
+impl Drop for RwLock {
+    fn drop(&mut self) {
+        if !self.rwlock.is_null() {
+            unsafe { uv_rwlock_destroy(self.rwlock) };
+
+            let layout = std::alloc::Layout::new::<uv_rwlock_t>();
+            unsafe { std::alloc::dealloc(self.rwlock as _, layout) };
+            self.rwlock = std::ptr::null_mut();
+        }
+    }
+}
+
+impl Inner<*mut uv_rwlock_t> for RwLock {
+    fn inner(&self) -> *mut uv_rwlock_t {
+        self.rwlock
+    }
+}
+
+/// A held read lock on a RwLock. The lock is released when the guard is dropped.
+pub struct ReadGuard<'a> {
+    rwlock: &'a RwLock,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ReadGuard<'_> {
+    /// Release the read lock. This is equivalent to dropping the guard.
+    pub fn rdunlock(self) {}
+}
+
+impl Drop for ReadGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { uv_rwlock_rdunlock(self.rwlock.rwlock) };
+    }
+}
+
+/// A held write lock on a RwLock. The lock is released when the guard is dropped.
+pub struct WriteGuard<'a> {
+    rwlock: &'a RwLock,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl WriteGuard<'_> {
+    /// Release the write lock. This is equivalent to dropping the guard.
+    pub fn wrunlock(self) {}
+}
+
+impl Drop for WriteGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { uv_rwlock_wrunlock(self.rwlock.rwlock) };
+    }
+}
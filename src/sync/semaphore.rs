@@ -0,0 +1,61 @@
+use crate::Inner;
+use uv::{uv_sem_destroy, uv_sem_init, uv_sem_post, uv_sem_t, uv_sem_trywait, uv_sem_wait};
+
+/// A counting semaphore backed by uv_sem_t. A common use is bounding the number of threadpool
+/// work callbacks that may use some resource at the same time.
+pub struct Semaphore {
+    sem: *mut uv_sem_t,
+}
+
+unsafe impl Send for Semaphore {}
+unsafe impl Sync for Semaphore {}
+
+impl Semaphore {
+    /// Create a new semaphore with the given initial count.
+    pub fn new(count: u32) -> crate::Result<Semaphore> {
+        let layout = std::alloc::Layout::new::<uv_sem_t>();
+        let sem = unsafe { std::alloc::alloc(layout) as *mut uv_sem_t };
+        if sem.is_null() {
+            return Err(crate::Error::ENOMEM);
+        }
+
+        let result = crate::uvret(unsafe { uv_sem_init(sem, count as _) });
+        if result.is_err() {
+            unsafe { std::alloc::dealloc(sem as _, layout) };
+        }
+        result.map(|_| Semaphore { sem })
+    }
+
+    /// Increment the count, waking up a thread blocked in wait() if there is one.
+    pub fn post(&self) {
+        unsafe { uv_sem_post(self.sem) };
+    }
+
+    /// Decrement the count, blocking the current thread until the count is greater than zero.
+    pub fn wait(&self) {
+        unsafe { uv_sem_wait(self.sem) };
+    }
+
+    /// Decrement the count without blocking. Returns EAGAIN if the count is zero.
+    pub fn try_wait(&self) -> crate::Result<()> {
+        crate::uvret(unsafe { uv_sem_trywait(self.sem) })
+    }
+}
+
+impl Drop for Semaphore {
+    fn drop(&mut self) {
+        if !self.sem.is_null() {
+            unsafe { uv_sem_destroy(self.sem) };
+
+            let layout = std::alloc::Layout::new::<uv_sem_t>();
+            unsafe { std::alloc::dealloc(self.sem as _, layout) };
+            self.sem = std::ptr::null_mut();
+        }
+    }
+}
+
+impl Inner<*mut uv_sem_t> for Semaphore {
+    fn inner(&self) -> *mut uv_sem_t {
+        self.sem
+    }
+}
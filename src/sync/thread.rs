@@ -0,0 +1,99 @@
+use std::ffi::c_void;
+use uv::{
+    uv_thread_create, uv_thread_create_ex, uv_thread_create_flags_UV_THREAD_HAS_STACK_SIZE,
+    uv_thread_equal, uv_thread_join, uv_thread_options_t, uv_thread_self, uv_thread_t,
+};
+
+type ThreadFn = Box<dyn FnOnce() + Send + 'static>;
+
+/// Entry point for threads created by Thread::spawn()
+extern "C" fn uv_thread_cb(arg: *mut c_void) {
+    let f: Box<ThreadFn> = unsafe { Box::from_raw(arg as _) };
+    f();
+}
+
+/// Options for Thread::spawn_with_options()
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadOptions {
+    /// The stack size for the new thread, in bytes. If None, the platform's default is used. The
+    /// size is rounded up to a multiple of the page size, and raised to the minimum stack size
+    /// supported by the platform if it is too small.
+    pub stack_size: Option<usize>,
+}
+
+impl ThreadOptions {
+    /// Create a new, default set of options
+    pub fn new() -> ThreadOptions {
+        Default::default()
+    }
+}
+
+/// Identifies a thread. ThreadIds can be compared to find out if two threads are the same.
+#[derive(Clone, Copy)]
+pub struct ThreadId(uv_thread_t);
+
+impl PartialEq for ThreadId {
+    fn eq(&self, other: &ThreadId) -> bool {
+        unsafe { uv_thread_equal(&self.0 as _, &other.0 as _) != 0 }
+    }
+}
+
+impl Eq for ThreadId {}
+
+/// A thread created with libuv's threading API, which interoperates with the other primitives in
+/// this module.
+///
+/// A thread's resources are only released once it has been joined, so every Thread should be
+/// joined eventually. If the closure panics, the process is aborted.
+pub struct Thread {
+    tid: uv_thread_t,
+}
+
+impl Thread {
+    /// Spawn a new thread that runs f.
+    pub fn spawn<F: FnOnce() + Send + 'static>(f: F) -> crate::Result<Thread> {
+        Thread::spawn_with_options(Default::default(), f)
+    }
+
+    /// Like spawn(), but with the given options.
+    pub fn spawn_with_options<F: FnOnce() + Send + 'static>(
+        options: ThreadOptions,
+        f: F,
+    ) -> crate::Result<Thread> {
+        let f: Box<ThreadFn> = Box::new(Box::new(f));
+        let arg = Box::into_raw(f);
+
+        let mut tid: uv_thread_t = unsafe { std::mem::zeroed() };
+        let result = crate::uvret(unsafe {
+            match options.stack_size {
+                Some(stack_size) => {
+                    let params = uv_thread_options_t {
+                        flags: uv_thread_create_flags_UV_THREAD_HAS_STACK_SIZE as _,
+                        stack_size: stack_size as _,
+                    };
+                    uv_thread_create_ex(&mut tid as _, &params as _, Some(uv_thread_cb), arg as _)
+                }
+                None => uv_thread_create(&mut tid as _, Some(uv_thread_cb), arg as _),
+            }
+        });
+        if result.is_err() {
+            std::mem::drop(unsafe { Box::from_raw(arg) });
+        }
+        result.map(|_| Thread { tid })
+    }
+
+    /// Returns the id of the calling thread.
+    pub fn self_id() -> ThreadId {
+        ThreadId(unsafe { uv_thread_self() })
+    }
+
+    /// Returns the id of this thread.
+    pub fn id(&self) -> ThreadId {
+        ThreadId(self.tid)
+    }
+
+    /// Block until this thread has finished running.
+    pub fn join(mut self) -> crate::Result<()> {
+        crate::uvret(unsafe { uv_thread_join(&mut self.tid as _) })
+    }
+}
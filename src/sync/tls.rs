@@ -0,0 +1,58 @@
+use std::marker::PhantomData;
+use uv::{uv_key_create, uv_key_delete, uv_key_get, uv_key_set, uv_key_t};
+
+/// A thread-local storage key backed by uv_key_t. Each thread sees its own value for the key,
+/// which starts out unset. Values are stored as Box<T> pointers: set() takes ownership of the new
+/// value and hands back the previous one.
+///
+/// Note: libuv does not run destructors for thread-local values. Values that are still set when a
+/// thread exits or when the key is dropped are leaked, so each thread should take() its value
+/// before it exits.
+pub struct TlsKey<T> {
+    key: uv_key_t,
+    _type: PhantomData<*mut T>,
+}
+
+unsafe impl<T> Send for TlsKey<T> {}
+unsafe impl<T> Sync for TlsKey<T> {}
+
+impl<T> TlsKey<T> {
+    /// Create a new thread-local storage key.
+    pub fn new() -> crate::Result<TlsKey<T>> {
+        let mut key: uv_key_t = unsafe { std::mem::zeroed() };
+        crate::uvret(unsafe { uv_key_create(&mut key as _) })?;
+        Ok(TlsKey {
+            key,
+            _type: PhantomData,
+        })
+    }
+
+    /// Returns a pointer to the calling thread's value, or null if it is unset. The pointer is
+    /// only valid until the value is replaced with set() or removed with take().
+    pub fn get(&self) -> *mut T {
+        unsafe { uv_key_get(&self.key as *const _ as *mut _) as _ }
+    }
+
+    /// Set the calling thread's value, returning the previous value, if any.
+    pub fn set(&self, value: Box<T>) -> Option<Box<T>> {
+        let previous = self.take();
+        unsafe { uv_key_set(&self.key as *const _ as *mut _, Box::into_raw(value) as _) };
+        previous
+    }
+
+    /// Remove and return the calling thread's value, if any.
+    pub fn take(&self) -> Option<Box<T>> {
+        let ptr = self.get();
+        if ptr.is_null() {
+            return None;
+        }
+        unsafe { uv_key_set(&self.key as *const _ as *mut _, std::ptr::null_mut()) };
+        Some(unsafe { Box::from_raw(ptr) })
+    }
+}
+
+impl<T> Drop for TlsKey<T> {
+    fn drop(&mut self) {
+        unsafe { uv_key_delete(&mut self.key as _) };
+    }
+}
@@ -0,0 +1,98 @@
+use libuv::{Buf, BufTrait};
+
+#[test]
+fn range_of_whole_buf() {
+    let buf = Buf::from_vec(b"hello".to_vec());
+    let readonly = buf.readonly();
+    assert_eq!(&readonly[..], b"hello");
+    assert_eq!(&readonly[1..4], b"ell");
+    assert_eq!(&readonly[5..], b"");
+    buf.into_vec();
+}
+
+#[test]
+fn empty_range_of_unallocated_buf() {
+    let buf = Buf::from_vec(Vec::new());
+    let readonly = buf.readonly();
+    assert_eq!(&readonly[..], b"");
+    assert_eq!(&readonly[0..0], b"");
+    buf.into_vec();
+}
+
+#[test]
+#[should_panic]
+fn range_past_end_of_unallocated_buf() {
+    let buf = Buf::from_vec(Vec::new());
+    let _ = &buf.readonly()[0..1];
+}
+
+#[test]
+fn resize_within_capacity() {
+    let mut buf = Buf::with_capacity(16).unwrap();
+    assert_eq!(buf.len(), 16);
+    assert_eq!(buf.capacity(), 16);
+
+    buf.resize(4).unwrap();
+    assert_eq!(buf.len(), 4);
+    assert_eq!(buf.capacity(), 16);
+
+    buf.resize(16).unwrap();
+    assert_eq!(buf.len(), 16);
+    assert_eq!(buf.resize(17), Err(libuv::Error::EINVAL));
+    assert_eq!(buf.len(), 16);
+    buf.destroy();
+}
+
+#[test]
+fn copy_from_slice_reuses_allocation() {
+    let mut buf = Buf::with_capacity(8).unwrap();
+
+    assert_eq!(buf.copy_from_slice(b"abc"), 3);
+    assert_eq!(buf.len(), 3);
+    assert_eq!(buf.as_slice(), b"abc");
+
+    assert_eq!(buf.copy_from_slice(b"0123456789"), 8);
+    assert_eq!(buf.len(), 8);
+    assert_eq!(buf.capacity(), 8);
+    assert_eq!(buf.as_slice(), b"01234567");
+
+    assert_eq!(buf.copy_from_slice(b""), 0);
+    assert!(buf.is_empty());
+    assert_eq!(buf.capacity(), 8);
+    buf.destroy();
+}
+
+#[test]
+fn from_vec_keeps_capacity() {
+    let mut v = Vec::with_capacity(32);
+    v.extend_from_slice(b"abc");
+    let ptr = v.as_ptr();
+
+    let buf = Buf::from_vec(v);
+    assert_eq!(buf.len(), 3);
+    assert_eq!(buf.capacity(), 32);
+    assert_eq!(buf.as_slice(), b"abc");
+
+    let v = buf.into_vec();
+    assert_eq!(v.as_ptr(), ptr);
+    assert_eq!(v.len(), 3);
+    assert_eq!(v.capacity(), 32);
+    assert_eq!(v, b"abc");
+}
+
+#[test]
+fn into_vec_of_empty_buf() {
+    let buf = Buf::from_vec(Vec::new());
+    assert!(!buf.is_allocated());
+    assert_eq!(buf.capacity(), 0);
+    assert!(buf.into_vec().is_empty());
+}
+
+#[test]
+fn into_vec_after_shrinking() {
+    let mut buf = Buf::from_vec(b"hello world".to_vec());
+    buf.resize(5).unwrap();
+    let v = buf.into_vec();
+    assert_eq!(v, b"hello");
+    assert_eq!(v.capacity(), 11);
+}
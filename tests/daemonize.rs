@@ -0,0 +1,59 @@
+#![cfg(unix)]
+
+/// Returns true if fd refers to /dev/null.
+unsafe fn is_devnull(fd: i32) -> bool {
+    let mut devnull: libc::stat = std::mem::zeroed();
+    let mut stat: libc::stat = std::mem::zeroed();
+    libc::stat(b"/dev/null\0".as_ptr() as _, &mut devnull) == 0
+        && libc::fstat(fd, &mut stat) == 0
+        && stat.st_dev == devnull.st_dev
+        && stat.st_ino == devnull.st_ino
+}
+
+#[test]
+fn daemonize() {
+    unsafe {
+        let mut fds = [0; 2];
+        assert_eq!(libc::pipe(fds.as_mut_ptr()), 0);
+        let (read_fd, write_fd) = (fds[0], fds[1]);
+        let test_sid = libc::getsid(0);
+
+        // daemonize() in a child, so that it is the child that exits rather than the test
+        let child = libc::fork();
+        assert!(child >= 0);
+        if child == 0 {
+            libc::close(read_fd);
+            if libuv::daemonize().is_err() {
+                libc::_exit(2);
+            }
+
+            // only the grandchild gets here: report what it looks like, then exit without
+            // returning to the test harness
+            let report = [
+                (libc::getsid(0) != test_sid) as u8,
+                (libc::getsid(0) != libc::getpid()) as u8,
+                is_devnull(libc::STDIN_FILENO) as u8,
+                is_devnull(libc::STDOUT_FILENO) as u8,
+                is_devnull(libc::STDERR_FILENO) as u8,
+            ];
+            libc::write(write_fd, report.as_ptr() as _, report.len());
+            libc::_exit(0);
+        }
+        libc::close(write_fd);
+
+        // the child exits successfully once the grandchild has been set up
+        let mut status = 0;
+        assert_eq!(libc::waitpid(child, &mut status, 0), child);
+        assert!(libc::WIFEXITED(status));
+        assert_eq!(libc::WEXITSTATUS(status), 0);
+
+        let mut report = [0u8; 5];
+        let n = libc::read(read_fd, report.as_mut_ptr() as _, report.len());
+        libc::close(read_fd);
+        assert_eq!(n, report.len() as isize);
+
+        // the grandchild is in a new session, but is not its leader (so it cannot acquire a
+        // controlling terminal), and its stdio points at /dev/null
+        assert_eq!(report, [1, 1, 1, 1, 1]);
+    }
+}
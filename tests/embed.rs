@@ -0,0 +1,53 @@
+//! Runs the same epoll + backend_timeout() + run_ready() cycle as examples/embed.rs.
+#![cfg(target_os = "linux")]
+
+use libuv::prelude::*;
+use libuv::TimerHandle;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn embedded_loop_fires_timer() {
+    let mut r#loop = Loop::new().unwrap();
+    let fired = Rc::new(Cell::new(false));
+
+    let on_timer_fired = fired.clone();
+    let mut timer = r#loop.timer().unwrap();
+    timer
+        .start(10, 0, move |mut handle: TimerHandle| {
+            on_timer_fired.set(true);
+            handle.close(());
+        })
+        .unwrap();
+
+    let epfd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+    assert!(epfd >= 0);
+    let mut event = libc::epoll_event {
+        events: libc::EPOLLIN as _,
+        u64: 0,
+    };
+    let ret =
+        unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, r#loop.backend_fd(), &mut event) };
+    assert_eq!(ret, 0);
+
+    // the timer closes itself, so the loop runs out of work once it has fired
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut alive = r#loop.run_ready().unwrap();
+    while alive {
+        assert!(Instant::now() < deadline, "timer did not fire");
+        let timeout = r#loop.backend_timeout();
+        assert!(timeout <= 10);
+
+        let mut events: [libc::epoll_event; 1] = unsafe { std::mem::zeroed() };
+        let ret = unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 1, timeout) };
+        assert!(
+            ret >= 0 || std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted
+        );
+
+        alive = r#loop.run_ready().unwrap();
+    }
+
+    unsafe { libc::close(epfd) };
+    assert!(fired.get());
+}
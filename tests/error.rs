@@ -0,0 +1,114 @@
+use libuv::{Error, FromInner};
+use std::collections::HashSet;
+
+/// Checks that each code maps to the variant of the same name and back, and that no two codes
+/// share a variant.
+macro_rules! check_round_trip {
+    ($($name:ident => $code:path),+ $(,)?) => {{
+        let mut codes = HashSet::new();
+        let mut variants = HashSet::new();
+        $(
+            let err = Error::from_inner($code);
+            assert_eq!(err, Error::$name, "{}", stringify!($name));
+            assert_eq!(err.code(), $code as i32, "{}", stringify!($name));
+            assert!(codes.insert($code), "duplicate code for {}", stringify!($name));
+            assert!(variants.insert(format!("{:?}", err)), "duplicate variant {:?}", err);
+        )+
+    }};
+}
+
+#[test]
+fn every_error_code_round_trips() {
+    check_round_trip!(
+        E2BIG => libuv::uv_errno_t_UV_E2BIG,
+        EACCES => libuv::uv_errno_t_UV_EACCES,
+        EADDRINUSE => libuv::uv_errno_t_UV_EADDRINUSE,
+        EADDRNOTAVAIL => libuv::uv_errno_t_UV_EADDRNOTAVAIL,
+        EAFNOSUPPORT => libuv::uv_errno_t_UV_EAFNOSUPPORT,
+        EAGAIN => libuv::uv_errno_t_UV_EAGAIN,
+        EAI_ADDRFAMILY => libuv::uv_errno_t_UV_EAI_ADDRFAMILY,
+        EAI_AGAIN => libuv::uv_errno_t_UV_EAI_AGAIN,
+        EAI_BADFLAGS => libuv::uv_errno_t_UV_EAI_BADFLAGS,
+        EAI_BADHINTS => libuv::uv_errno_t_UV_EAI_BADHINTS,
+        EAI_CANCELED => libuv::uv_errno_t_UV_EAI_CANCELED,
+        EAI_FAIL => libuv::uv_errno_t_UV_EAI_FAIL,
+        EAI_FAMILY => libuv::uv_errno_t_UV_EAI_FAMILY,
+        EAI_MEMORY => libuv::uv_errno_t_UV_EAI_MEMORY,
+        EAI_NODATA => libuv::uv_errno_t_UV_EAI_NODATA,
+        EAI_NONAME => libuv::uv_errno_t_UV_EAI_NONAME,
+        EAI_OVERFLOW => libuv::uv_errno_t_UV_EAI_OVERFLOW,
+        EAI_PROTOCOL => libuv::uv_errno_t_UV_EAI_PROTOCOL,
+        EAI_SERVICE => libuv::uv_errno_t_UV_EAI_SERVICE,
+        EAI_SOCKTYPE => libuv::uv_errno_t_UV_EAI_SOCKTYPE,
+        EALREADY => libuv::uv_errno_t_UV_EALREADY,
+        EBADF => libuv::uv_errno_t_UV_EBADF,
+        EBUSY => libuv::uv_errno_t_UV_EBUSY,
+        ECANCELED => libuv::uv_errno_t_UV_ECANCELED,
+        ECHARSET => libuv::uv_errno_t_UV_ECHARSET,
+        ECONNABORTED => libuv::uv_errno_t_UV_ECONNABORTED,
+        ECONNREFUSED => libuv::uv_errno_t_UV_ECONNREFUSED,
+        ECONNRESET => libuv::uv_errno_t_UV_ECONNRESET,
+        EDESTADDRREQ => libuv::uv_errno_t_UV_EDESTADDRREQ,
+        EEXIST => libuv::uv_errno_t_UV_EEXIST,
+        EFAULT => libuv::uv_errno_t_UV_EFAULT,
+        EFBIG => libuv::uv_errno_t_UV_EFBIG,
+        EFTYPE => libuv::uv_errno_t_UV_EFTYPE,
+        EHOSTDOWN => libuv::uv_errno_t_UV_EHOSTDOWN,
+        EHOSTUNREACH => libuv::uv_errno_t_UV_EHOSTUNREACH,
+        EILSEQ => libuv::uv_errno_t_UV_EILSEQ,
+        EINTR => libuv::uv_errno_t_UV_EINTR,
+        EINVAL => libuv::uv_errno_t_UV_EINVAL,
+        EIO => libuv::uv_errno_t_UV_EIO,
+        EISCONN => libuv::uv_errno_t_UV_EISCONN,
+        EISDIR => libuv::uv_errno_t_UV_EISDIR,
+        ELOOP => libuv::uv_errno_t_UV_ELOOP,
+        EMFILE => libuv::uv_errno_t_UV_EMFILE,
+        EMLINK => libuv::uv_errno_t_UV_EMLINK,
+        EMSGSIZE => libuv::uv_errno_t_UV_EMSGSIZE,
+        ENAMETOOLONG => libuv::uv_errno_t_UV_ENAMETOOLONG,
+        ENETDOWN => libuv::uv_errno_t_UV_ENETDOWN,
+        ENETUNREACH => libuv::uv_errno_t_UV_ENETUNREACH,
+        ENFILE => libuv::uv_errno_t_UV_ENFILE,
+        ENOBUFS => libuv::uv_errno_t_UV_ENOBUFS,
+        ENODATA => libuv::uv_errno_t_UV_ENODATA,
+        ENODEV => libuv::uv_errno_t_UV_ENODEV,
+        ENOENT => libuv::uv_errno_t_UV_ENOENT,
+        ENOMEM => libuv::uv_errno_t_UV_ENOMEM,
+        ENONET => libuv::uv_errno_t_UV_ENONET,
+        ENOPROTOOPT => libuv::uv_errno_t_UV_ENOPROTOOPT,
+        ENOSPC => libuv::uv_errno_t_UV_ENOSPC,
+        ENOSYS => libuv::uv_errno_t_UV_ENOSYS,
+        ENOTCONN => libuv::uv_errno_t_UV_ENOTCONN,
+        ENOTDIR => libuv::uv_errno_t_UV_ENOTDIR,
+        ENOTEMPTY => libuv::uv_errno_t_UV_ENOTEMPTY,
+        ENOTSOCK => libuv::uv_errno_t_UV_ENOTSOCK,
+        ENOTSUP => libuv::uv_errno_t_UV_ENOTSUP,
+        ENOTTY => libuv::uv_errno_t_UV_ENOTTY,
+        ENXIO => libuv::uv_errno_t_UV_ENXIO,
+        EOF => libuv::uv_errno_t_UV_EOF,
+        EOVERFLOW => libuv::uv_errno_t_UV_EOVERFLOW,
+        EPERM => libuv::uv_errno_t_UV_EPERM,
+        EPIPE => libuv::uv_errno_t_UV_EPIPE,
+        EPROTO => libuv::uv_errno_t_UV_EPROTO,
+        EPROTONOSUPPORT => libuv::uv_errno_t_UV_EPROTONOSUPPORT,
+        EPROTOTYPE => libuv::uv_errno_t_UV_EPROTOTYPE,
+        ERANGE => libuv::uv_errno_t_UV_ERANGE,
+        EREMOTEIO => libuv::uv_errno_t_UV_EREMOTEIO,
+        EROFS => libuv::uv_errno_t_UV_EROFS,
+        ERRNO_MAX => libuv::uv_errno_t_UV_ERRNO_MAX,
+        ESHUTDOWN => libuv::uv_errno_t_UV_ESHUTDOWN,
+        ESOCKTNOSUPPORT => libuv::uv_errno_t_UV_ESOCKTNOSUPPORT,
+        ESPIPE => libuv::uv_errno_t_UV_ESPIPE,
+        ESRCH => libuv::uv_errno_t_UV_ESRCH,
+        ETIMEDOUT => libuv::uv_errno_t_UV_ETIMEDOUT,
+        ETXTBSY => libuv::uv_errno_t_UV_ETXTBSY,
+        EUNATCH => libuv::uv_errno_t_UV_EUNATCH,
+        EXDEV => libuv::uv_errno_t_UV_EXDEV,
+        UNKNOWN => libuv::uv_errno_t_UV_UNKNOWN,
+    );
+}
+
+#[test]
+fn unknown_codes_map_to_unknown() {
+    assert_eq!(Error::from_inner(1), Error::UNKNOWN);
+}
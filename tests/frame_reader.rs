@@ -0,0 +1,99 @@
+use libuv::{FrameEndianness, FrameReader};
+
+/// Encodes body as a frame with a 2 byte big endian length header.
+fn frame(body: &[u8]) -> Vec<u8> {
+    let mut frame = (body.len() as u16).to_be_bytes().to_vec();
+    frame.extend_from_slice(body);
+    frame
+}
+
+#[test]
+fn frame_split_across_reads() {
+    let mut reader = FrameReader::new(2, FrameEndianness::Big, 1024).unwrap();
+    let data = frame(b"hello world");
+    let mut frames = Vec::new();
+
+    // split inside the header, then inside the body
+    reader.feed(&data[..1], |f| frames.push(f)).unwrap();
+    assert!(frames.is_empty());
+    assert_eq!(reader.buffered_len(), 1);
+
+    reader.feed(&data[1..6], |f| frames.push(f)).unwrap();
+    assert!(frames.is_empty());
+    assert_eq!(reader.buffered_len(), 6);
+
+    reader.feed(&data[6..], |f| frames.push(f)).unwrap();
+    assert_eq!(frames, vec![b"hello world".to_vec()]);
+    assert_eq!(reader.buffered_len(), 0);
+}
+
+#[test]
+fn frame_fed_one_byte_at_a_time() {
+    let mut reader = FrameReader::new(4, FrameEndianness::Little, 1024).unwrap();
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(b"abc");
+    let mut frames = Vec::new();
+
+    for b in &data {
+        reader.feed(&[*b], |f| frames.push(f)).unwrap();
+    }
+    assert_eq!(frames, vec![b"abc".to_vec()]);
+    assert_eq!(reader.buffered_len(), 0);
+}
+
+#[test]
+fn several_frames_in_one_read() {
+    let mut reader = FrameReader::new(2, FrameEndianness::Big, 1024).unwrap();
+    let mut data = frame(b"one");
+    data.extend(frame(b""));
+    data.extend(frame(b"three"));
+    let partial = frame(b"four");
+    data.extend_from_slice(&partial[..3]);
+    let mut frames = Vec::new();
+
+    reader.feed(&data, |f| frames.push(f)).unwrap();
+    assert_eq!(
+        frames,
+        vec![b"one".to_vec(), b"".to_vec(), b"three".to_vec()]
+    );
+    assert_eq!(reader.buffered_len(), 3);
+
+    reader.feed(&partial[3..], |f| frames.push(f)).unwrap();
+    assert_eq!(frames.last().unwrap(), b"four");
+    assert_eq!(reader.buffered_len(), 0);
+}
+
+#[test]
+fn oversized_frame_is_rejected() {
+    let mut reader = FrameReader::new(2, FrameEndianness::Big, 4).unwrap();
+    let mut data = frame(b"ok");
+    data.extend(frame(b"too long"));
+    let mut frames = Vec::new();
+
+    let result = reader.feed(&data, |f| frames.push(f));
+    assert_eq!(result, Err(libuv::Error::EMSGSIZE));
+    assert_eq!(frames, vec![b"ok".to_vec()]);
+    assert_eq!(reader.buffered_len(), 0);
+}
+
+#[test]
+fn oversized_frame_is_rejected_from_header_alone() {
+    let mut reader = FrameReader::new(2, FrameEndianness::Big, 4).unwrap();
+    let result = reader.feed(&[0, 5], |_| panic!("no frame expected"));
+    assert_eq!(result, Err(libuv::Error::EMSGSIZE));
+}
+
+#[test]
+fn unaddressable_frame_is_rejected() {
+    // the length passes the max_frame_size check, but the end of the frame overflows a usize
+    let mut reader = FrameReader::new(8, FrameEndianness::Big, usize::MAX).unwrap();
+    let result = reader.feed(&[0xff; 8], |_| panic!("no frame expected"));
+    assert_eq!(result, Err(libuv::Error::EMSGSIZE));
+    assert_eq!(reader.buffered_len(), 0);
+}
+
+#[test]
+fn invalid_header_len() {
+    assert!(FrameReader::new(0, FrameEndianness::Big, 1024).is_err());
+    assert!(FrameReader::new(9, FrameEndianness::Big, 1024).is_err());
+}
@@ -0,0 +1,147 @@
+#![cfg(feature = "futures")]
+
+use libuv::prelude::*;
+use libuv::{PipeHandle, TcpBindFlags, TcpHandle};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+fn noop_raw_waker() -> RawWaker {
+    fn clone(_: *const ()) -> RawWaker {
+        noop_raw_waker()
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    RawWaker::new(std::ptr::null(), &VTABLE)
+}
+
+/// Polls the future once. The loop has already been run, so it is expected to be ready.
+fn poll_ready<F: Future + Unpin>(future: &mut F) -> F::Output {
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    match Pin::new(future).poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is not ready"),
+    }
+}
+
+/// Creates a pair of connected pipes.
+#[cfg(unix)]
+fn pipe_pair(r#loop: &Loop) -> (PipeHandle, PipeHandle) {
+    let mut fds = [0; 2];
+    let ret = unsafe { libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, fds.as_mut_ptr()) };
+    assert_eq!(ret, 0);
+
+    let mut a = r#loop.pipe(false).unwrap();
+    let mut b = r#loop.pipe(false).unwrap();
+    a.open(fds[0]).unwrap();
+    b.open(fds[1]).unwrap();
+    (a, b)
+}
+
+/// Reads everything from stream until EOF, then closes it.
+#[cfg(unix)]
+fn read_to_end(stream: &mut PipeHandle) -> Rc<RefCell<Vec<u8>>> {
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let on_read_received = received.clone();
+    stream
+        .read_start_alloc(
+            move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => on_read_received.borrow_mut().extend(data),
+                Err(e) => {
+                    assert_eq!(e, libuv::Error::EOF);
+                    stream.close(());
+                }
+            },
+        )
+        .unwrap();
+    received
+}
+
+#[test]
+fn fs_stat_async() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut future = r#loop.fs_stat_async("Cargo.toml");
+    r#loop.run(RunMode::Default).unwrap();
+
+    let stat = poll_ready(&mut future).unwrap();
+    assert!(stat.size > 0);
+}
+
+#[test]
+fn fs_stat_async_missing_file() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut future = r#loop.fs_stat_async("does-not-exist");
+    r#loop.run(RunMode::Default).unwrap();
+
+    let err = poll_ready(&mut future).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<libuv::Error>(),
+        Some(&libuv::Error::ENOENT)
+    );
+}
+
+#[test]
+fn tcp_connect_async() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut server = r#loop.tcp().unwrap();
+    server
+        .bind(&"127.0.0.1:0".parse().unwrap(), TcpBindFlags::empty())
+        .unwrap();
+    let addr = server.getsockname().unwrap();
+    server
+        .listen(1, |mut server: StreamHandle, status: libuv::Result<u32>| {
+            assert!(status.is_ok());
+            server.close(());
+        })
+        .unwrap();
+
+    let mut client: TcpHandle = r#loop.tcp().unwrap();
+    let mut future = client.connect_async(&addr);
+    r#loop.run(RunMode::Default).unwrap();
+
+    poll_ready(&mut future).unwrap();
+    client.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn write_async() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+    let received = read_to_end(&mut reader);
+
+    let data: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+    let mut future = writer.write_async(data.clone());
+    while writer.pending_write_count() > 0 {
+        r#loop.run(RunMode::Once).unwrap();
+    }
+
+    assert_eq!(poll_ready(&mut future), Ok(data.len()));
+    writer.close(());
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(*received.borrow(), data);
+}
+
+#[cfg(unix)]
+#[test]
+fn write_async_dropped_future() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+    let received = read_to_end(&mut reader);
+
+    // dropping the future does not cancel the write, and completing it must not touch the future
+    drop(writer.write_async(b"hello".to_vec()));
+    let mut future = writer.write_async(b" world".to_vec());
+    while writer.pending_write_count() > 0 {
+        r#loop.run(RunMode::Once).unwrap();
+    }
+
+    assert_eq!(poll_ready(&mut future), Ok(6));
+    writer.close(());
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(*received.borrow(), b"hello world");
+}
@@ -0,0 +1,51 @@
+use libuv::prelude::*;
+use libuv::{Inner, TimerHandle, ToHandle};
+
+#[test]
+fn debug_and_display_of_open_handle() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut timer = r#loop.timer().unwrap();
+    timer.start(60_000, 0, |_: TimerHandle| {}).unwrap();
+    let address: *mut libuv::uv_handle_t = timer.to_handle().inner();
+
+    assert_eq!(
+        format!("{:?}", timer),
+        format!(
+            "TimerHandle {{ type: \"timer\", address: {:?}, active: true, closing: false }}",
+            address
+        )
+    );
+    assert_eq!(
+        format!("{:?}", timer.to_handle()),
+        format!(
+            "Handle {{ type: \"timer\", address: {:?}, active: true, closing: false }}",
+            address
+        )
+    );
+    assert_eq!(
+        timer.to_handle().to_string(),
+        format!("timer handle at {:p}", address)
+    );
+
+    timer.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[test]
+fn debug_of_closing_handle() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut timer = r#loop.timer().unwrap();
+    timer.start(60_000, 0, |_: TimerHandle| {}).unwrap();
+    timer.close(());
+    let address: *mut libuv::uv_handle_t = timer.to_handle().inner();
+
+    assert_eq!(
+        format!("{:?}", timer),
+        format!(
+            "TimerHandle {{ type: \"timer\", address: {:?}, active: false, closing: true }}",
+            address
+        )
+    );
+
+    r#loop.run(RunMode::Default).unwrap();
+}
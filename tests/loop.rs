@@ -0,0 +1,153 @@
+use libuv::prelude::*;
+use libuv::{HandleType, Inner, TimerHandle, ToHandle};
+use std::cell::RefCell;
+use std::net::SocketAddr;
+use std::rc::Rc;
+
+#[test]
+fn force_close_then_drop() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut timer = r#loop.timer().unwrap();
+    timer.start(60_000, 0, |_: TimerHandle| {}).unwrap();
+
+    let leaked = r#loop.force_close().unwrap();
+    assert_eq!(leaked, vec![HandleType::TIMER]);
+
+    // the loop has already been closed, so dropping it must not close it again
+    drop(r#loop);
+}
+
+#[test]
+fn close_then_drop() {
+    let mut r#loop = Loop::new().unwrap();
+    r#loop.close().unwrap();
+    drop(r#loop);
+}
+
+#[test]
+fn closed_loop_stays_usable_until_dropped() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut clone = r#loop.clone();
+    r#loop.close().unwrap();
+
+    // closing again, or force closing, does nothing
+    r#loop.close().unwrap();
+    assert_eq!(r#loop.force_close().unwrap(), vec![]);
+
+    assert_eq!(clone.run(RunMode::Default), Err(libuv::Error::EINVAL));
+    assert!(!clone.is_alive());
+    let mut handles = 0;
+    clone.walk(|_| handles += 1);
+    assert_eq!(handles, 0);
+    clone.set_data(Box::new(1u32));
+    assert_eq!(r#loop.take_data::<u32>(), Some(Box::new(1)));
+    r#loop.now();
+
+    drop(clone);
+    drop(r#loop);
+}
+
+#[test]
+fn walk_detailed_reports_handle_state() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut started = r#loop.timer().unwrap();
+    started.start(60_000, 0, |_: TimerHandle| {}).unwrap();
+    let mut unrefd = r#loop.timer().unwrap();
+    unrefd.start(60_000, 0, |_: TimerHandle| {}).unwrap();
+    unrefd.unref();
+    let mut closing = r#loop.timer().unwrap();
+    closing.close(());
+
+    let mut seen = Vec::new();
+    r#loop.walk_detailed(|handle, has_ref, is_active, is_closing| {
+        let handle: *mut libuv::uv_handle_t = handle.inner();
+        seen.push((handle, has_ref, is_active, is_closing));
+    });
+    let state = |handle: &TimerHandle| {
+        let handle: *mut libuv::uv_handle_t = handle.to_handle().inner();
+        seen.iter()
+            .find(|(h, ..)| *h == handle)
+            .map(|(_, has_ref, is_active, is_closing)| (*has_ref, *is_active, *is_closing))
+            .unwrap()
+    };
+
+    assert_eq!(seen.len(), 3);
+    assert_eq!(state(&started), (true, true, false));
+    assert_eq!(state(&unrefd), (false, true, false));
+    let (_, is_active, is_closing) = state(&closing);
+    assert!(!is_active);
+    assert!(is_closing);
+
+    r#loop.force_close().unwrap();
+}
+
+#[test]
+fn resolve_localhost() {
+    let mut r#loop = Loop::new().unwrap();
+    let result = Rc::new(RefCell::new(None));
+
+    let on_resolve_result = result.clone();
+    r#loop
+        .resolve(
+            "localhost",
+            8080,
+            move |addrs: libuv::Result<Vec<SocketAddr>>| {
+                *on_resolve_result.borrow_mut() = Some(addrs);
+            },
+        )
+        .unwrap();
+    r#loop.run(RunMode::Default).unwrap();
+
+    let addrs = result.borrow_mut().take().unwrap().unwrap();
+    assert!(!addrs.is_empty());
+    assert!(addrs.iter().all(|addr| addr.port() == 8080));
+    assert!(addrs.iter().all(|addr| addr.ip().is_loopback()));
+}
+
+#[test]
+fn run_adaptive_re_refs_background_timers() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut background = r#loop.timer().unwrap();
+    background.start(5, 5, |_: TimerHandle| {}).unwrap();
+
+    // by the time this fires, the loop has been idle for longer than the threshold, so the
+    // repeating timer has been unref'd and only this timer keeps the loop alive
+    let ref_while_idle = Rc::new(RefCell::new(None));
+    let on_timer_ref = ref_while_idle.clone();
+    let mut foreground = r#loop.timer().unwrap();
+    foreground
+        .start(200, 0, move |_: TimerHandle| {
+            *on_timer_ref.borrow_mut() = Some(background.has_ref());
+        })
+        .unwrap();
+
+    r#loop.run_adaptive(20).unwrap();
+    assert_eq!(*ref_while_idle.borrow(), Some(false));
+    assert!(background.has_ref());
+    assert!(background.is_active());
+
+    background.close(());
+    foreground.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[test]
+fn with_data() {
+    let mut r#loop = Loop::new().unwrap();
+    assert_eq!(r#loop.with_data(|n: &mut u32| *n), None);
+
+    r#loop.set_data(Box::new(1u32));
+    assert_eq!(r#loop.with_data(|n: &mut u32| *n += 1), Some(()));
+    assert_eq!(r#loop.with_data(|s: &mut String| s.len()), None);
+    assert_eq!(r#loop.clone().with_data(|n: &mut u32| *n), Some(2));
+
+    // the data is detached while the closure runs, so replacing it there is safe
+    let mut clone = r#loop.clone();
+    let seen = r#loop.with_data(|n: &mut u32| {
+        assert_eq!(clone.with_data(|n: &mut u32| *n), None);
+        clone.set_data(Box::new(10u32));
+        *n
+    });
+    assert_eq!(seen, Some(2));
+    assert_eq!(r#loop.take_data::<u32>(), Some(Box::new(10)));
+}
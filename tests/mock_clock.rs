@@ -0,0 +1,150 @@
+#![cfg(feature = "mock-clock")]
+
+use libuv::{MockClock, MockTimer};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Fired = Rc<RefCell<Vec<(&'static str, u64)>>>;
+
+/// Returns a callback that records the name of the timer and the time it fired at.
+fn record(clock: &MockClock, fired: &Fired, name: &'static str) -> impl FnMut(MockTimer) {
+    let clock = clock.clone();
+    let fired = fired.clone();
+    move |_: MockTimer| fired.borrow_mut().push((name, clock.now()))
+}
+
+#[test]
+fn timers_fire_in_order() {
+    let clock = MockClock::new();
+    let fired = Fired::default();
+
+    let mut c = MockTimer::new(&clock);
+    c.start(30, 0, record(&clock, &fired, "c"));
+    let mut a = MockTimer::new(&clock);
+    a.start(10, 0, record(&clock, &fired, "a"));
+    let mut b1 = MockTimer::new(&clock);
+    b1.start(20, 0, record(&clock, &fired, "b1"));
+    let mut b2 = MockTimer::new(&clock);
+    b2.start(20, 0, record(&clock, &fired, "b2"));
+
+    clock.advance(25);
+    assert_eq!(*fired.borrow(), vec![("a", 10), ("b1", 20), ("b2", 20)]);
+    assert_eq!(clock.now(), 25);
+    assert!(c.is_active());
+    assert_eq!(c.get_due_in(), 5);
+
+    clock.advance(5);
+    assert_eq!(fired.borrow().last(), Some(&("c", 30)));
+    assert!(!c.is_active());
+}
+
+#[test]
+fn repeating_timer() {
+    let clock = MockClock::new();
+    let fired = Fired::default();
+
+    let mut timer = MockTimer::new(&clock);
+    timer.start(10, 5, record(&clock, &fired, "t"));
+    clock.advance(27);
+    assert_eq!(
+        *fired.borrow(),
+        vec![("t", 10), ("t", 15), ("t", 20), ("t", 25)]
+    );
+    assert_eq!(timer.get_due_in(), 3);
+
+    timer.stop();
+    clock.advance(100);
+    assert_eq!(fired.borrow().len(), 4);
+    assert_eq!(timer.get_due_in(), 0);
+}
+
+#[test]
+fn again() {
+    let clock = MockClock::new();
+    let fired = Fired::default();
+
+    let mut timer = MockTimer::new(&clock);
+    assert_eq!(timer.again(), Err(libuv::Error::EINVAL));
+
+    // again() restarts a repeating timer using the repeat value as the timeout
+    timer.start(50, 10, record(&clock, &fired, "t"));
+    clock.advance(5);
+    timer.again().unwrap();
+    assert_eq!(timer.get_due_in(), 10);
+    clock.advance(10);
+    assert_eq!(*fired.borrow(), vec![("t", 15)]);
+
+    // and stops a timer that doesn't repeat
+    timer.set_repeat(0);
+    timer.again().unwrap();
+    assert!(!timer.is_active());
+}
+
+#[test]
+fn callbacks_can_start_and_stop_timers() {
+    let clock = MockClock::new();
+    let fired = Fired::default();
+
+    // a timer that starts another one with a timeout of zero, which fires during the same advance()
+    let mut other = MockTimer::new(&clock);
+    let mut starter = MockTimer::new(&clock);
+    let on_timer_clock = clock.clone();
+    let on_timer_fired = fired.clone();
+    starter.start(10, 0, move |_: MockTimer| {
+        on_timer_fired
+            .borrow_mut()
+            .push(("starter", on_timer_clock.now()));
+        other.start(0, 0, record(&on_timer_clock, &on_timer_fired, "other"));
+    });
+
+    // a repeating timer that stops itself the second time it fires
+    let mut stopper = MockTimer::new(&clock);
+    let on_timer_clock = clock.clone();
+    let on_timer_fired = fired.clone();
+    stopper.start(20, 20, move |mut timer: MockTimer| {
+        on_timer_fired
+            .borrow_mut()
+            .push(("stopper", on_timer_clock.now()));
+        if on_timer_clock.now() >= 40 {
+            timer.stop();
+        }
+    });
+
+    // a timer that restarts itself with a new callback
+    let mut restarter = MockTimer::new(&clock);
+    let on_timer_clock = clock.clone();
+    let on_timer_fired = fired.clone();
+    restarter.start(15, 0, move |mut timer: MockTimer| {
+        on_timer_fired
+            .borrow_mut()
+            .push(("restarter", on_timer_clock.now()));
+        timer.start(50, 0, record(&on_timer_clock, &on_timer_fired, "restarted"));
+    });
+
+    clock.advance(100);
+    assert_eq!(
+        *fired.borrow(),
+        vec![
+            ("starter", 10),
+            ("other", 10),
+            ("restarter", 15),
+            ("stopper", 20),
+            ("stopper", 40),
+            ("restarted", 65),
+        ]
+    );
+    assert!(!stopper.is_active());
+}
+
+#[test]
+fn repeating_timer_stops_at_the_end_of_time() {
+    let clock = MockClock::new();
+    let fired = Fired::default();
+
+    let mut timer = MockTimer::new(&clock);
+    timer.start(0, u64::MAX / 2 + 1, record(&clock, &fired, "t"));
+    clock.advance(u64::MAX);
+    assert_eq!(*fired.borrow(), vec![("t", 0), ("t", u64::MAX / 2 + 1)]);
+    assert!(!timer.is_active());
+    assert_eq!(clock.now(), u64::MAX);
+}
@@ -0,0 +1,42 @@
+use libuv::{os_environ_map, os_getenv_ci, setenv, unsetenv};
+
+#[test]
+fn getenv_ci_ignores_case() {
+    setenv("LIBUV_RS_TEST_GETENV_CI", "value").unwrap();
+    assert_eq!(
+        os_getenv_ci("libuv_rs_test_getenv_ci").unwrap(),
+        Some("value".to_string())
+    );
+    assert_eq!(
+        os_environ_map()
+            .unwrap()
+            .get("LIBUV_RS_TEST_GETENV_CI")
+            .map(String::as_str),
+        Some("value")
+    );
+    unsetenv("LIBUV_RS_TEST_GETENV_CI").unwrap();
+    assert_eq!(os_getenv_ci("libuv_rs_test_getenv_ci").unwrap(), None);
+}
+
+// on Windows, names that differ only by case refer to the same variable
+#[cfg(unix)]
+#[test]
+fn getenv_ci_is_deterministic() {
+    setenv("libuv_rs_test_CASES", "lower").unwrap();
+    setenv("LIBUV_RS_TEST_CASES", "upper").unwrap();
+    setenv("Libuv_Rs_Test_Cases", "title").unwrap();
+
+    // an exact match wins, otherwise the name that sorts first
+    assert_eq!(
+        os_getenv_ci("libuv_rs_test_CASES").unwrap(),
+        Some("lower".to_string())
+    );
+    assert_eq!(
+        os_getenv_ci("libuv_rs_test_cases").unwrap(),
+        Some("upper".to_string())
+    );
+
+    unsetenv("libuv_rs_test_CASES").unwrap();
+    unsetenv("LIBUV_RS_TEST_CASES").unwrap();
+    unsetenv("Libuv_Rs_Test_Cases").unwrap();
+}
@@ -0,0 +1,90 @@
+use libuv::prelude::*;
+
+#[test]
+fn unbound_pipe_is_not_abstract() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut pipe = r#loop.pipe(false).unwrap();
+    assert!(!pipe.is_abstract());
+
+    pipe.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn filesystem_name_is_not_abstract() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut pipe = r#loop.pipe(false).unwrap();
+    let path = std::env::temp_dir().join(format!("libuv-rs-test-{}.sock", std::process::id()));
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    pipe.bind(path).unwrap();
+    assert!(!pipe.is_abstract());
+    assert_eq!(pipe.getsockname_bytes().unwrap(), path.as_bytes());
+    assert_eq!(pipe.getsockname().unwrap(), path);
+
+    pipe.close(());
+    r#loop.run(RunMode::Default).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use libuv::prelude::*;
+
+    /// Builds the address of the given abstract name (without the leading null byte), returning
+    /// the address and its length.
+    fn abstract_addr(name: &[u8]) -> (libc::sockaddr_un, libc::socklen_t) {
+        let mut addr: libc::sockaddr_un = unsafe { std::mem::zeroed() };
+        addr.sun_family = libc::AF_UNIX as _;
+        for (i, b) in name.iter().enumerate() {
+            addr.sun_path[i + 1] = *b as _;
+        }
+        let len = std::mem::size_of::<libc::sa_family_t>() + 1 + name.len();
+        (addr, len as _)
+    }
+
+    fn socket() -> i32 {
+        let fd = unsafe { libc::socket(libc::AF_UNIX, libc::SOCK_STREAM, 0) };
+        assert!(fd >= 0);
+        fd
+    }
+
+    #[test]
+    fn abstract_names_with_null_bytes() {
+        let mut r#loop = Loop::new().unwrap();
+
+        // an abstract name with an embedded null byte
+        let name = format!("libuv-rs\0test-{}", std::process::id()).into_bytes();
+        let mut expected = vec![0];
+        expected.extend_from_slice(&name);
+        let (addr, len) = abstract_addr(&name);
+
+        let server_fd = socket();
+        let client_fd = socket();
+        unsafe {
+            assert_eq!(libc::bind(server_fd, &addr as *const _ as _, len), 0);
+            assert_eq!(libc::listen(server_fd, 1), 0);
+            assert_eq!(libc::connect(client_fd, &addr as *const _ as _, len), 0);
+        }
+
+        let mut server = r#loop.pipe(false).unwrap();
+        server.open(server_fd).unwrap();
+        let mut client = r#loop.pipe(false).unwrap();
+        client.open(client_fd).unwrap();
+
+        assert!(server.is_abstract());
+        assert_eq!(server.getsockname_bytes().unwrap(), expected);
+        assert!(!client.is_abstract());
+        assert_eq!(client.getpeername_bytes().unwrap(), expected);
+        assert_eq!(
+            client.getpeername().unwrap(),
+            String::from_utf8(expected).unwrap()
+        );
+
+        server.close(());
+        client.close(());
+        r#loop.run(RunMode::Default).unwrap();
+    }
+}
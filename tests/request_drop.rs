@@ -0,0 +1,77 @@
+use libuv::prelude::*;
+use libuv::WorkReq;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Counts the allocations that are live on the current thread, so tests running on other threads
+/// don't disturb the count.
+struct CountingAlloc;
+
+thread_local! {
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = LIVE.try_with(|live| live.set(live.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = LIVE.try_with(|live| live.set(live.get() - 1));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn live_allocations() -> isize {
+    LIVE.with(|live| live.get())
+}
+
+#[test]
+fn dropping_unsubmitted_work_req_frees_it() {
+    let before = live_allocations();
+    let req = WorkReq::new(|_: WorkReq| {}, ()).unwrap();
+    assert!(live_allocations() > before);
+
+    drop(req);
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn dropping_submitted_work_req_does_not_destroy_it() {
+    let mut r#loop = Loop::new().unwrap();
+    let done = Rc::new(Cell::new(false));
+    let after_done = done.clone();
+    let req = r#loop
+        .queue_work(
+            |_: WorkReq| {},
+            move |_: WorkReq, status: libuv::Result<u32>| {
+                assert!(status.is_ok());
+                after_done.set(true);
+            },
+        )
+        .unwrap();
+
+    // libuv owns the request now, so dropping our handle to it must not free it
+    drop(req);
+    r#loop.run(RunMode::Default).unwrap();
+    assert!(done.get());
+}
+
+#[test]
+fn dropping_sync_fs_req_frees_it() {
+    let r#loop = Loop::new().unwrap();
+    let before = live_allocations();
+
+    // without a callback the request runs synchronously, and libuv never destroys it
+    let req = r#loop.fs_stat("Cargo.toml", ()).unwrap();
+    assert!(req.result().is_ok());
+    assert!(live_allocations() > before);
+
+    drop(req);
+    assert_eq!(live_allocations(), before);
+}
@@ -0,0 +1,104 @@
+use libuv::prelude::*;
+use libuv::ResolverCache;
+use std::cell::RefCell;
+use std::net::SocketAddr;
+use std::rc::Rc;
+
+type Results = Rc<RefCell<Vec<libuv::Result<Vec<SocketAddr>>>>>;
+
+/// Returns a callback that records each result it is called with.
+fn record(results: &Results) -> impl FnMut(libuv::Result<Vec<SocketAddr>>) {
+    let results = results.clone();
+    move |addrs: libuv::Result<Vec<SocketAddr>>| results.borrow_mut().push(addrs)
+}
+
+#[test]
+fn cache_hit() {
+    let mut r#loop = Loop::new().unwrap();
+    let cache = ResolverCache::new(60_000);
+    let results = Results::default();
+
+    cache
+        .resolve(&r#loop, "localhost", 80, record(&results))
+        .unwrap();
+    assert!(results.borrow().is_empty());
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(results.borrow().len(), 1);
+    assert_eq!(cache.len(), 1);
+
+    // the cached result is delivered before resolve() returns, without a new lookup
+    cache
+        .resolve(&r#loop, "localhost", 80, record(&results))
+        .unwrap();
+    assert_eq!(cache.pending_lookups(), 0);
+    let results = results.borrow();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0], results[1]);
+    assert!(results[1].as_ref().unwrap().iter().all(|a| a.port() == 80));
+}
+
+#[test]
+fn expired_entries_are_looked_up_again() {
+    let mut r#loop = Loop::new().unwrap();
+    let cache = ResolverCache::new(10);
+    let results = Results::default();
+
+    cache
+        .resolve(&r#loop, "localhost", 80, record(&results))
+        .unwrap();
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(results.borrow().len(), 1);
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    r#loop.update_time();
+
+    cache
+        .resolve(&r#loop, "localhost", 80, record(&results))
+        .unwrap();
+    assert_eq!(results.borrow().len(), 1);
+    assert_eq!(cache.pending_lookups(), 1);
+    assert!(cache.is_empty());
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(results.borrow().len(), 2);
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn concurrent_lookups_are_coalesced() {
+    let mut r#loop = Loop::new().unwrap();
+    let cache = ResolverCache::new(60_000);
+    let results = Results::default();
+
+    for _ in 0..5 {
+        cache
+            .resolve(&r#loop, "localhost", 80, record(&results))
+            .unwrap();
+    }
+    assert_eq!(cache.pending_lookups(), 1);
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(cache.pending_lookups(), 0);
+    let results = results.borrow();
+    assert_eq!(results.len(), 5);
+    assert!(results.iter().all(|r| *r == results[0]));
+}
+
+#[test]
+fn different_ports_are_looked_up_separately() {
+    let mut r#loop = Loop::new().unwrap();
+    let cache = ResolverCache::new(60_000);
+    let results = Results::default();
+
+    cache
+        .resolve(&r#loop, "localhost", 80, record(&results))
+        .unwrap();
+    cache
+        .resolve(&r#loop, "localhost", 443, record(&results))
+        .unwrap();
+    assert_eq!(cache.pending_lookups(), 2);
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(results.borrow().len(), 2);
+    assert_eq!(cache.len(), 2);
+}
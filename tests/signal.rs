@@ -0,0 +1,40 @@
+#![cfg(unix)]
+
+use libuv::prelude::*;
+use libuv::SignalHandle;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn start_until_stops_when_callback_returns_false() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut signal = r#loop.signal().unwrap();
+    let calls = Rc::new(Cell::new(0));
+
+    let on_signal_calls = calls.clone();
+    signal
+        .start_until(
+            move |_: SignalHandle, signum: i32| {
+                assert_eq!(signum, libc::SIGUSR1);
+                on_signal_calls.set(on_signal_calls.get() + 1);
+                if on_signal_calls.get() < 3 {
+                    // the handler is still installed, so this is delivered to the callback again
+                    unsafe { libc::kill(libc::getpid(), libc::SIGUSR1) };
+                    true
+                } else {
+                    false
+                }
+            },
+            libc::SIGUSR1,
+        )
+        .unwrap();
+    unsafe { libc::kill(libc::getpid(), libc::SIGUSR1) };
+
+    // the loop runs out of work once the handle has been stopped
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(calls.get(), 3);
+    assert!(!signal.is_active());
+
+    signal.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
@@ -0,0 +1,574 @@
+#![cfg(unix)]
+
+use libuv::prelude::*;
+use libuv::{Buf, PipeHandle, ReadonlyBuf, TimerHandle, WriteReq};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Creates a pair of connected pipes.
+fn pipe_pair(r#loop: &Loop) -> (PipeHandle, PipeHandle) {
+    let mut fds = [0; 2];
+    let ret = unsafe { libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, fds.as_mut_ptr()) };
+    assert_eq!(ret, 0);
+
+    let mut a = r#loop.pipe(false).unwrap();
+    let mut b = r#loop.pipe(false).unwrap();
+    a.open(fds[0]).unwrap();
+    b.open(fds[1]).unwrap();
+    (a, b)
+}
+
+#[test]
+fn read_start_alloc_reads_everything_in_order() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+
+    // larger than the buffer libuv suggests, so the read buffer is reused
+    let data: Vec<u8> = (0..200_000).map(|i| i as u8).collect();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let eof = Rc::new(RefCell::new(false));
+
+    let on_read_received = received.clone();
+    let on_read_eof = eof.clone();
+    reader
+        .read_start_alloc(
+            move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => {
+                    assert!(!data.is_empty());
+                    on_read_received.borrow_mut().extend(data);
+                }
+                Err(e) => {
+                    assert_eq!(e, libuv::Error::EOF);
+                    *on_read_eof.borrow_mut() = true;
+                    stream.close(());
+                }
+            },
+        )
+        .unwrap();
+
+    writer
+        .write_owned_vectored(
+            vec![data[..100_000].to_vec(), data[100_000..].to_vec()],
+            |req: WriteReq, status: libuv::Result<u32>| {
+                assert!(status.is_ok());
+                req.handle().close(());
+            },
+        )
+        .unwrap();
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert!(*eof.borrow());
+    assert_eq!(*received.borrow(), data);
+}
+
+#[test]
+fn no_reads_while_paused() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let reads_while_paused = Rc::new(RefCell::new(None));
+
+    let on_read_received = received.clone();
+    reader
+        .read_start_alloc(
+            move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => {
+                    let first = on_read_received.borrow().is_empty();
+                    on_read_received.borrow_mut().push(data);
+                    if first {
+                        // pause, then send more data that must not be read until resume()
+                        stream.pause().unwrap();
+                        writer
+                            .write_owned_vectored(
+                                vec![b"second".to_vec()],
+                                |req: WriteReq, status: libuv::Result<u32>| {
+                                    assert!(status.is_ok());
+                                    req.handle().close(());
+                                },
+                            )
+                            .unwrap();
+                    }
+                }
+                Err(e) => {
+                    assert_eq!(e, libuv::Error::EOF);
+                    stream.close(());
+                }
+            },
+        )
+        .unwrap();
+    writer
+        .write_owned_vectored(vec![b"first".to_vec()], ())
+        .unwrap();
+
+    let mut timer = r#loop.timer().unwrap();
+    let on_timer_received = received.clone();
+    let on_timer_reads = reads_while_paused.clone();
+    timer
+        .start(100, 0, move |mut timer: TimerHandle| {
+            *on_timer_reads.borrow_mut() = Some(on_timer_received.borrow().len());
+            reader.resume().unwrap();
+            timer.close(());
+        })
+        .unwrap();
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(*reads_while_paused.borrow(), Some(1));
+    assert_eq!(received.borrow()[0], b"first");
+    assert_eq!(received.borrow()[1..].concat(), b"second");
+}
+
+#[test]
+fn empty_try_write() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut a, mut b) = pipe_pair(&r#loop);
+    let empty = libuv::Buf::from_vec(Vec::new());
+
+    assert_eq!(a.try_write(&[] as &[libuv::Buf]), Ok(0));
+    assert_eq!(a.try_write(&[empty]), Ok(0));
+
+    empty.into_vec();
+    a.close(());
+    b.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[test]
+fn empty_write() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+    let empty = libuv::Buf::from_vec(Vec::new());
+    let statuses = Rc::new(RefCell::new(Vec::new()));
+    let received = Rc::new(RefCell::new(Vec::new()));
+
+    let on_read_received = received.clone();
+    reader
+        .read_start_alloc(
+            move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => on_read_received.borrow_mut().extend(data),
+                Err(_) => stream.close(()),
+            },
+        )
+        .unwrap();
+
+    let on_write_statuses = statuses.clone();
+    writer
+        .write(
+            &[] as &[libuv::Buf],
+            move |_: WriteReq, status: libuv::Result<u32>| {
+                on_write_statuses.borrow_mut().push(status)
+            },
+        )
+        .unwrap();
+    let on_write_statuses = statuses.clone();
+    writer
+        .write(
+            &[empty],
+            move |req: WriteReq, status: libuv::Result<u32>| {
+                on_write_statuses.borrow_mut().push(status);
+                req.handle().close(());
+            },
+        )
+        .unwrap();
+
+    r#loop.run(RunMode::Default).unwrap();
+    empty.into_vec();
+    assert_eq!(*statuses.borrow(), vec![Ok(0), Ok(0)]);
+    assert!(received.borrow().is_empty());
+}
+
+#[test]
+fn write_owned_vectored_in_order() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let status = Rc::new(RefCell::new(None));
+
+    let on_read_received = received.clone();
+    reader
+        .read_start_alloc(
+            move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => on_read_received.borrow_mut().extend(data),
+                Err(_) => stream.close(()),
+            },
+        )
+        .unwrap();
+
+    let on_write_status = status.clone();
+    writer
+        .write_owned_vectored(
+            vec![
+                b"one ".to_vec(),
+                b"two ".to_vec(),
+                Vec::new(),
+                b"three ".to_vec(),
+                b"four".to_vec(),
+            ],
+            move |req: WriteReq, status: libuv::Result<u32>| {
+                *on_write_status.borrow_mut() = Some(status);
+                req.handle().close(());
+            },
+        )
+        .unwrap();
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(*status.borrow(), Some(Ok(0)));
+    assert_eq!(*received.borrow(), b"one two three four");
+}
+
+#[test]
+fn set_read_callback_from_read_callback() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+    let first = Rc::new(RefCell::new(Vec::new()));
+    let second = Rc::new(RefCell::new(Vec::new()));
+
+    let on_read_first = first.clone();
+    let on_read_second = second.clone();
+    reader
+        .read_start(
+            |_: libuv::Handle, suggested_size: usize| {
+                libuv::Buf::with_capacity(suggested_size).ok()
+            },
+            move |mut stream: StreamHandle,
+                  nread: libuv::Result<usize>,
+                  mut buf: libuv::ReadonlyBuf| {
+                let len = nread.unwrap();
+                on_read_first
+                    .borrow_mut()
+                    .extend_from_slice(buf.as_slice(len));
+                buf.dealloc();
+                if len == 0 {
+                    return;
+                }
+
+                // switch callbacks, then send the data that the new callback should receive
+                let on_read_second = on_read_second.clone();
+                stream
+                    .set_read_callback(
+                        move |mut stream: StreamHandle,
+                              nread: libuv::Result<usize>,
+                              mut buf: libuv::ReadonlyBuf| {
+                            match nread {
+                                Ok(len) => on_read_second
+                                    .borrow_mut()
+                                    .extend_from_slice(buf.as_slice(len)),
+                                Err(e) => {
+                                    assert_eq!(e, libuv::Error::EOF);
+                                    stream.close(());
+                                }
+                            }
+                            buf.dealloc();
+                        },
+                    )
+                    .unwrap();
+                writer
+                    .write_owned_vectored(
+                        vec![b"second".to_vec()],
+                        |req: WriteReq, status: libuv::Result<u32>| {
+                            assert!(status.is_ok());
+                            req.handle().close(());
+                        },
+                    )
+                    .unwrap();
+            },
+        )
+        .unwrap();
+    writer
+        .write_owned_vectored(vec![b"first".to_vec()], ())
+        .unwrap();
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(*first.borrow(), b"first");
+    assert_eq!(*second.borrow(), b"second");
+}
+
+#[test]
+fn pause_then_resume_from_read_callback() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+    let received = Rc::new(RefCell::new(Vec::new()));
+
+    let on_read_received = received.clone();
+    reader
+        .read_start_alloc(
+            move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => {
+                    let first = on_read_received.borrow().is_empty();
+                    on_read_received.borrow_mut().extend(data);
+                    if first {
+                        // the callback is still in place, so resume() can restart reading
+                        stream.pause().unwrap();
+                        stream.resume().unwrap();
+                        writer
+                            .write_owned_vectored(
+                                vec![b" second".to_vec()],
+                                |req: WriteReq, status: libuv::Result<u32>| {
+                                    assert!(status.is_ok());
+                                    req.handle().close(());
+                                },
+                            )
+                            .unwrap();
+                    }
+                }
+                Err(e) => {
+                    assert_eq!(e, libuv::Error::EOF);
+                    stream.close(());
+                }
+            },
+        )
+        .unwrap();
+    writer
+        .write_owned_vectored(vec![b"first".to_vec()], ())
+        .unwrap();
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert_eq!(*received.borrow(), b"first second");
+}
+
+#[test]
+fn proxy_backpressure() {
+    const HIGH_WATER: usize = 64 * 1024;
+    const LOW_WATER: usize = 16 * 1024;
+
+    let mut r#loop = Loop::new().unwrap();
+    let (mut source, mut producer) = pipe_pair(&r#loop);
+    let (mut destination, mut consumer) = pipe_pair(&r#loop);
+
+    // much more than fits in the socket buffers, so the destination's write queue grows while the
+    // consumer isn't reading
+    let data: Vec<u8> = (0..4_000_000).map(|i| (i % 251) as u8).collect();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let pauses = Rc::new(RefCell::new(0));
+    let resumes = Rc::new(RefCell::new(0));
+    let max_queue_size = Rc::new(RefCell::new(0));
+
+    producer
+        .write_owned_vectored(
+            vec![data.clone()],
+            |req: WriteReq, status: libuv::Result<u32>| {
+                assert!(status.is_ok());
+                req.handle().close(());
+            },
+        )
+        .unwrap();
+
+    // the proxy: forward everything from source to destination, pausing the source while the
+    // destination's write queue is above the high water mark
+    let paused = Rc::new(RefCell::new(false));
+    let on_read_pauses = pauses.clone();
+    let on_read_resumes = resumes.clone();
+    let on_read_max_queue_size = max_queue_size.clone();
+    source
+        .read_start_alloc(
+            move |mut source: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => {
+                    let paused = paused.clone();
+                    let resumes = on_read_resumes.clone();
+                    destination
+                        .write_owned_vectored(
+                            vec![data],
+                            move |req: WriteReq, status: libuv::Result<u32>| {
+                                assert!(status.is_ok());
+                                let destination = req.handle();
+                                if *paused.borrow()
+                                    && destination.get_write_queue_size() <= LOW_WATER
+                                {
+                                    *paused.borrow_mut() = false;
+                                    *resumes.borrow_mut() += 1;
+                                    source.resume().unwrap();
+                                }
+                            },
+                        )
+                        .unwrap();
+
+                    let queue_size = destination.get_write_queue_size();
+                    let mut max_queue_size = on_read_max_queue_size.borrow_mut();
+                    *max_queue_size = queue_size.max(*max_queue_size);
+                    if queue_size > HIGH_WATER {
+                        *paused.borrow_mut() = true;
+                        *on_read_pauses.borrow_mut() += 1;
+                        source.pause().unwrap();
+                    }
+                }
+                Err(e) => {
+                    assert_eq!(e, libuv::Error::EOF);
+                    source.close(());
+                    destination
+                        .shutdown(|req: libuv::ShutdownReq, status: libuv::Result<u32>| {
+                            assert!(status.is_ok());
+                            req.handle().close(());
+                        })
+                        .unwrap();
+                }
+            },
+        )
+        .unwrap();
+
+    // the consumer only starts reading after a while, so the destination backs up first
+    let mut timer = r#loop.timer().unwrap();
+    let on_read_received = received.clone();
+    timer
+        .start(100, 0, move |mut timer: TimerHandle| {
+            consumer
+                .read_start_alloc(
+                    move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                        Ok(data) => on_read_received.borrow_mut().extend(data),
+                        Err(e) => {
+                            assert_eq!(e, libuv::Error::EOF);
+                            stream.close(());
+                        }
+                    },
+                )
+                .unwrap();
+            timer.close(());
+        })
+        .unwrap();
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert!(*pauses.borrow() > 0);
+    assert!(*resumes.borrow() > 0);
+
+    // the source is paused as soon as the queue passes the high water mark, so the queue never
+    // grows by more than one read's worth of data beyond it
+    assert!(*max_queue_size.borrow() <= HIGH_WATER + 256 * 1024);
+    assert!(*received.borrow() == data);
+}
+
+#[test]
+fn echo() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut server, mut client) = pipe_pair(&r#loop);
+    let data: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let eof = Rc::new(RefCell::new(false));
+
+    server.echo().unwrap();
+
+    let on_read_received = received.clone();
+    let on_read_eof = eof.clone();
+    client
+        .read_start_alloc(
+            move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => on_read_received.borrow_mut().extend(data),
+                Err(e) => {
+                    // the echo side shuts down its write side once it has seen our EOF
+                    assert_eq!(e, libuv::Error::EOF);
+                    *on_read_eof.borrow_mut() = true;
+                    stream.close(());
+                }
+            },
+        )
+        .unwrap();
+    client.write_owned_vectored(vec![data.clone()], ()).unwrap();
+    client
+        .shutdown(|_: libuv::ShutdownReq, status: libuv::Result<u32>| assert!(status.is_ok()))
+        .unwrap();
+
+    // the echo side is still reading until it closes itself, so the loop only runs out of work
+    // once both ends have been closed
+    r#loop.run(RunMode::Default).unwrap();
+    assert!(*eof.borrow());
+    assert_eq!(*received.borrow(), data);
+}
+
+#[test]
+fn pending_write_count() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut reader, mut writer) = pipe_pair(&r#loop);
+    let counts = Rc::new(RefCell::new(Vec::new()));
+
+    assert_eq!(writer.pending_write_count(), 0);
+    for _ in 0..3 {
+        let on_write_counts = counts.clone();
+        writer
+            .write_owned_vectored(
+                vec![b"data".to_vec()],
+                move |req: WriteReq, status: libuv::Result<u32>| {
+                    assert!(status.is_ok());
+                    on_write_counts
+                        .borrow_mut()
+                        .push(req.handle().pending_write_count());
+                },
+            )
+            .unwrap();
+    }
+
+    // callbacks are never called synchronously, even if the data was written immediately
+    assert_eq!(writer.pending_write_count(), 3);
+    while writer.pending_write_count() > 0 {
+        r#loop.run(RunMode::Once).unwrap();
+    }
+
+    // the count is decremented before each callback is called
+    assert_eq!(*counts.borrow(), vec![2, 1, 0]);
+    reader.close(());
+    writer.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[test]
+fn write_buf_forwards_read_buffers() {
+    let mut r#loop = Loop::new().unwrap();
+    let (mut source, mut producer) = pipe_pair(&r#loop);
+    let (mut destination, mut consumer) = pipe_pair(&r#loop);
+    let data: Vec<u8> = (0..1_000_000).map(|i| (i % 251) as u8).collect();
+    let received = Rc::new(RefCell::new(Vec::new()));
+
+    producer
+        .write_owned_vectored(
+            vec![data.clone()],
+            |req: WriteReq, status: libuv::Result<u32>| {
+                assert!(status.is_ok());
+                req.handle().close(());
+            },
+        )
+        .unwrap();
+
+    // hand each buffer that was read from source straight to destination, without copying it
+    source
+        .read_start(
+            |_: Handle, suggested_size: usize| Buf::with_capacity(suggested_size).ok(),
+            move |mut source: StreamHandle, nread: libuv::Result<usize>, mut buf: ReadonlyBuf| {
+                match nread {
+                    Ok(0) => buf.dealloc(),
+                    Ok(len) => {
+                        destination
+                            .write_buf(
+                                buf.into_buf(),
+                                len,
+                                |_: WriteReq, status: libuv::Result<u32>| assert!(status.is_ok()),
+                            )
+                            .unwrap();
+                    }
+                    Err(e) => {
+                        buf.dealloc();
+                        assert_eq!(e, libuv::Error::EOF);
+                        source.close(());
+                        destination
+                            .shutdown(|req: libuv::ShutdownReq, status: libuv::Result<u32>| {
+                                assert!(status.is_ok());
+                                req.handle().close(());
+                            })
+                            .unwrap();
+                    }
+                }
+            },
+        )
+        .unwrap();
+
+    let on_read_received = received.clone();
+    consumer
+        .read_start_alloc(
+            move |mut stream: StreamHandle, data: libuv::Result<Vec<u8>>| match data {
+                Ok(data) => on_read_received.borrow_mut().extend(data),
+                Err(e) => {
+                    assert_eq!(e, libuv::Error::EOF);
+                    stream.close(());
+                }
+            },
+        )
+        .unwrap();
+
+    r#loop.run(RunMode::Default).unwrap();
+    assert!(*received.borrow() == data);
+}
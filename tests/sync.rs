@@ -0,0 +1,287 @@
+use libuv::{Barrier, Cond, Mutex, Once, RwLock, Semaphore, Thread, ThreadOptions, TlsKey};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+const THREADS: usize = 4;
+const ITERATIONS: usize = 10_000;
+
+/// Runs f on THREADS threads at once, and waits for all of them to finish.
+fn on_threads(f: impl Fn() + Send + Sync + 'static) {
+    let f = Arc::new(f);
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let f = f.clone();
+            std::thread::spawn(move || f())
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+/// Increments count in two steps, so that increments are lost unless the caller holds a lock.
+fn unsynchronized_increment(count: &AtomicUsize) {
+    let value = count.load(Ordering::Relaxed);
+    std::thread::yield_now();
+    count.store(value + 1, Ordering::Relaxed);
+}
+
+#[test]
+fn mutex_contention() {
+    let mutex = Arc::new(Mutex::new().unwrap());
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let thread_count = count.clone();
+    on_threads(move || {
+        for _ in 0..ITERATIONS {
+            let _guard = mutex.lock();
+            unsynchronized_increment(&thread_count);
+        }
+    });
+    assert_eq!(count.load(Ordering::SeqCst), THREADS * ITERATIONS);
+}
+
+#[test]
+fn mutex_try_lock() {
+    let mutex = Arc::new(Mutex::new().unwrap());
+    let guard = mutex.lock();
+
+    let thread_mutex = mutex.clone();
+    let busy = std::thread::spawn(move || thread_mutex.try_lock().err())
+        .join()
+        .unwrap();
+    assert_eq!(busy, Some(libuv::Error::EBUSY));
+
+    guard.unlock();
+    assert!(mutex.try_lock().is_ok());
+}
+
+#[test]
+fn recursive_mutex() {
+    let mutex = Mutex::new_recursive().unwrap();
+    let outer = mutex.lock();
+    let inner = mutex.try_lock().unwrap();
+    inner.unlock();
+    outer.unlock();
+}
+
+#[test]
+fn rwlock_contention() {
+    let rwlock = Arc::new(RwLock::new().unwrap());
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let thread_count = count.clone();
+    on_threads(move || {
+        for i in 0..ITERATIONS {
+            if i % 2 == 0 {
+                let _guard = rwlock.wrlock();
+                unsynchronized_increment(&thread_count);
+            } else {
+                let _guard = rwlock.rdlock();
+                thread_count.load(Ordering::Relaxed);
+            }
+        }
+    });
+    assert_eq!(count.load(Ordering::SeqCst), THREADS * ITERATIONS / 2);
+}
+
+#[test]
+fn rwlock_try_locks() {
+    let rwlock = Arc::new(RwLock::new().unwrap());
+
+    // readers share the lock, but exclude writers
+    let reader = rwlock.rdlock();
+    let thread_rwlock = rwlock.clone();
+    let (read, write) = std::thread::spawn(move || {
+        (
+            thread_rwlock.tryrdlock().is_ok(),
+            thread_rwlock.trywrlock().err(),
+        )
+    })
+    .join()
+    .unwrap();
+    assert!(read);
+    assert_eq!(write, Some(libuv::Error::EBUSY));
+    reader.rdunlock();
+
+    // a writer excludes everyone
+    let writer = rwlock.wrlock();
+    let thread_rwlock = rwlock.clone();
+    let (read, write) = std::thread::spawn(move || {
+        (
+            thread_rwlock.tryrdlock().err(),
+            thread_rwlock.trywrlock().err(),
+        )
+    })
+    .join()
+    .unwrap();
+    assert_eq!(read, Some(libuv::Error::EBUSY));
+    assert_eq!(write, Some(libuv::Error::EBUSY));
+    writer.wrunlock();
+
+    assert!(rwlock.trywrlock().is_ok());
+}
+
+#[test]
+fn semaphore() {
+    let sem = Arc::new(Semaphore::new(1).unwrap());
+    sem.wait();
+    assert_eq!(sem.try_wait(), Err(libuv::Error::EAGAIN));
+
+    // each post from another thread releases one wait
+    let thread_sem = sem.clone();
+    let poster = std::thread::spawn(move || {
+        for _ in 0..THREADS {
+            thread_sem.post();
+        }
+    });
+    for _ in 0..THREADS {
+        sem.wait();
+    }
+    poster.join().unwrap();
+    assert_eq!(sem.try_wait(), Err(libuv::Error::EAGAIN));
+
+    sem.post();
+    assert_eq!(sem.try_wait(), Ok(()));
+}
+
+#[test]
+fn cond_signal() {
+    let state = Arc::new((
+        Mutex::new().unwrap(),
+        Cond::new().unwrap(),
+        AtomicBool::new(false),
+    ));
+
+    let thread_state = state.clone();
+    let signaler = std::thread::spawn(move || {
+        let (mutex, cond, ready) = &*thread_state;
+        let _guard = mutex.lock();
+        ready.store(true, Ordering::SeqCst);
+        cond.signal();
+    });
+
+    let (mutex, cond, ready) = &*state;
+    let mut guard = mutex.lock();
+    while !ready.load(Ordering::SeqCst) {
+        cond.wait(&mut guard);
+    }
+    guard.unlock();
+    signaler.join().unwrap();
+}
+
+#[test]
+fn cond_timedwait_times_out() {
+    let mutex = Mutex::new().unwrap();
+    let cond = Cond::new().unwrap();
+    let mut guard = mutex.lock();
+
+    // nothing signals the condition variable, so every wait ends with the timeout (other than
+    // spurious wakeups)
+    let start = Instant::now();
+    while cond.timedwait(&mut guard, 10_000_000) {}
+    assert!(start.elapsed() >= Duration::from_millis(10));
+}
+
+#[test]
+fn barrier_has_one_serializer() {
+    const ROUNDS: usize = 3;
+    let barrier = Arc::new(Barrier::new(THREADS as u32).unwrap());
+    let serializers = Arc::new(AtomicUsize::new(0));
+
+    let thread_serializers = serializers.clone();
+    on_threads(move || {
+        for _ in 0..ROUNDS {
+            if barrier.wait() {
+                thread_serializers.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    });
+    assert_eq!(serializers.load(Ordering::SeqCst), ROUNDS);
+}
+
+#[test]
+fn thread_spawn_and_join() {
+    let (tx, rx) = mpsc::channel();
+    let thread = Thread::spawn(move || tx.send(std::thread::current().id()).unwrap()).unwrap();
+    assert!(thread.id() == thread.id());
+    assert!(thread.id() != Thread::self_id());
+    thread.join().unwrap();
+
+    assert_ne!(rx.recv().unwrap(), std::thread::current().id());
+}
+
+#[test]
+fn thread_stack_size() {
+    const BUF_SIZE: usize = 4 * 1024 * 1024;
+
+    // a tiny stack size is raised to the platform's minimum
+    let options = ThreadOptions {
+        stack_size: Some(1),
+    };
+    Thread::spawn_with_options(options, || ())
+        .unwrap()
+        .join()
+        .unwrap();
+
+    // a large one fits a big local buffer
+    let (tx, rx) = mpsc::channel();
+    let options = ThreadOptions {
+        stack_size: Some(4 * BUF_SIZE),
+    };
+    let thread = Thread::spawn_with_options(options, move || {
+        let buf = [1u8; BUF_SIZE];
+        tx.send(buf.iter().map(|b| *b as usize).sum::<usize>())
+            .unwrap();
+    })
+    .unwrap();
+    thread.join().unwrap();
+    assert_eq!(rx.recv().unwrap(), BUF_SIZE);
+}
+
+#[test]
+fn tls_key_is_per_thread() {
+    let key = Arc::new(TlsKey::<usize>::new().unwrap());
+    assert!(key.get().is_null());
+    assert_eq!(key.set(Box::new(0)), None);
+
+    // every thread starts out unset, and only sees its own value
+    let barrier = Arc::new(Barrier::new(THREADS as u32).unwrap());
+    let next = Arc::new(AtomicUsize::new(1));
+    let thread_key = key.clone();
+    on_threads(move || {
+        assert!(thread_key.get().is_null());
+        let value = next.fetch_add(1, Ordering::SeqCst);
+        thread_key.set(Box::new(value));
+
+        // wait until every thread has set its value before checking it
+        barrier.wait();
+        assert_eq!(unsafe { *thread_key.get() }, value);
+        assert_eq!(thread_key.take(), Some(Box::new(value)));
+        assert!(thread_key.get().is_null());
+    });
+
+    assert_eq!(key.set(Box::new(10)), Some(Box::new(0)));
+    assert_eq!(key.take(), Some(Box::new(10)));
+}
+
+#[test]
+fn once_runs_exactly_once() {
+    static ONCE: Once = Once::new();
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    let barrier = Arc::new(Barrier::new(THREADS as u32).unwrap());
+    on_threads(move || {
+        barrier.wait();
+        ONCE.call_once(|| {
+            // the other threads block in call_once() until this has finished
+            std::thread::sleep(Duration::from_millis(10));
+            RUNS.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+    });
+
+    ONCE.call_once(|| panic!("ran twice"));
+    assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,92 @@
+use libuv::prelude::*;
+use libuv::{TcpFlags, TcpHandle};
+use std::time::Duration;
+
+#[cfg(unix)]
+fn getsockopt<T>(handle: &TcpHandle, level: i32, name: i32) -> T {
+    let fd = handle.get_fileno().unwrap();
+    let mut value: T = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<T>() as libc::socklen_t;
+    let ret =
+        unsafe { libc::getsockopt(fd, level, name, &mut value as *mut T as _, &mut len as _) };
+    assert_eq!(ret, 0);
+    value
+}
+
+#[cfg(unix)]
+#[test]
+fn set_tos() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut tcp = TcpHandle::new_ex(&r#loop, TcpFlags::AF_INET).unwrap();
+
+    // DSCP EF (46), shifted into the upper 6 bits
+    tcp.set_tos(46 << 2).unwrap();
+    let tos: libc::c_int = getsockopt(&tcp, libc::IPPROTO_IP, libc::IP_TOS);
+    assert_eq!(tos, 46 << 2);
+
+    tcp.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn set_linger() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut tcp = TcpHandle::new_ex(&r#loop, TcpFlags::AF_INET).unwrap();
+
+    tcp.set_linger(Some(Duration::from_millis(5500))).unwrap();
+    let linger: libc::linger = getsockopt(&tcp, libc::SOL_SOCKET, libc::SO_LINGER);
+    assert_ne!(linger.l_onoff, 0);
+    assert_eq!(linger.l_linger, 5);
+
+    tcp.set_linger(None).unwrap();
+    let linger: libc::linger = getsockopt(&tcp, libc::SOL_SOCKET, libc::SO_LINGER);
+    assert_eq!(linger.l_onoff, 0);
+
+    tcp.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn socket_options_without_socket() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut tcp = r#loop.tcp().unwrap();
+
+    assert_eq!(tcp.set_tos(0), Err(libuv::Error::EBADF));
+    assert_eq!(tcp.set_linger(None), Err(libuv::Error::EBADF));
+
+    tcp.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[cfg(windows)]
+#[test]
+fn socket_options_not_supported() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut tcp = TcpHandle::new_ex(&r#loop, TcpFlags::AF_INET).unwrap();
+
+    assert_eq!(tcp.set_tos(46 << 2), Err(libuv::Error::ENOTSUP));
+    assert_eq!(
+        tcp.set_linger(Some(Duration::from_secs(5))),
+        Err(libuv::Error::ENOTSUP)
+    );
+
+    tcp.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
+
+#[test]
+fn getsockname_before_bind() {
+    let mut r#loop = Loop::new().unwrap();
+    let mut tcp = r#loop.tcp().unwrap();
+
+    let err = tcp.getsockname().unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<libuv::Error>(),
+        Some(&libuv::Error::EINVAL)
+    );
+
+    tcp.close(());
+    r#loop.run(RunMode::Default).unwrap();
+}
@@ -0,0 +1,80 @@
+use libuv::prelude::*;
+use libuv::{Buf, UdpBindFlags, UdpHandle, UdpSendReq};
+use std::cell::Cell;
+use std::net::{SocketAddr, UdpSocket};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const PACKETS: usize = 1000;
+const PACKET_SIZE: usize = 64;
+const ROUNDS: u32 = 20;
+
+/// Calls send ROUNDS times, each time running a new loop until the sends complete, and returns
+/// the average time per round. send is given a bound UDP handle and the address of a socket that
+/// never reads, so packets that don't fit in its receive buffer are dropped by the kernel.
+fn time_rounds(mut send: impl FnMut(&UdpHandle, SocketAddr, Rc<Cell<usize>>)) -> Duration {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = receiver.local_addr().unwrap();
+
+    let mut total = Duration::default();
+    for _ in 0..ROUNDS {
+        let mut r#loop = Loop::new().unwrap();
+        let mut udp = r#loop.udp().unwrap();
+
+        // send_batch() can only use sendmmsg(2) once the handle has a socket
+        udp.bind(&"127.0.0.1:0".parse().unwrap(), UdpBindFlags::empty())
+            .unwrap();
+
+        let completed = Rc::new(Cell::new(0));
+        let start = Instant::now();
+        send(&udp, addr, completed.clone());
+        r#loop.run(RunMode::Default).unwrap();
+        total += start.elapsed();
+        assert_eq!(completed.get(), PACKETS);
+
+        udp.close(());
+        r#loop.run(RunMode::Default).unwrap();
+    }
+    total / ROUNDS
+}
+
+/// Compares send_batch() with calling send() once per packet. This is a benchmark rather than a
+/// test, so run it in release mode with:
+///
+///     cargo test --release --test udp -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_send_batch_vs_send() {
+    let payload = vec![0x5a; PACKET_SIZE];
+
+    let buf = Buf::from_vec(payload.clone());
+    let single = time_rounds(|udp, addr, completed| {
+        for _ in 0..PACKETS {
+            let completed = completed.clone();
+            udp.send(
+                Some(&addr),
+                &[buf],
+                move |_: UdpSendReq, status: libuv::Result<u32>| {
+                    assert!(status.is_ok());
+                    completed.set(completed.get() + 1);
+                },
+            )
+            .unwrap();
+        }
+    });
+    buf.into_vec();
+
+    let batch = time_rounds(|udp, addr, completed| {
+        let packets = (0..PACKETS).map(|_| (payload.clone(), addr)).collect();
+        udp.send_batch(packets, move |_: UdpHandle, status: libuv::Result<u32>| {
+            assert!(status.is_ok());
+            completed.set(PACKETS);
+        })
+        .unwrap();
+    });
+
+    eprintln!(
+        "{} packets of {} bytes, average of {} rounds: send() {:?}, send_batch() {:?}",
+        PACKETS, PACKET_SIZE, ROUNDS, single, batch
+    );
+}
@@ -0,0 +1,77 @@
+use libuv::prelude::*;
+use libuv::{FsModeFlags, FsOpenFlags, FsReq, WorkReq};
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn work_wait_returns_status() {
+    let mut r#loop = Loop::new().unwrap();
+    let worked = Rc::new(Cell::new(false));
+    let status = Rc::new(Cell::new(None));
+
+    let on_work_worked = worked.clone();
+    let on_after_work_status = status.clone();
+    let req = r#loop
+        .queue_work(
+            move |_: WorkReq| on_work_worked.set(true),
+            move |_: WorkReq, status: libuv::Result<u32>| on_after_work_status.set(Some(status)),
+        )
+        .unwrap();
+
+    // the after_work_cb is still called, with the same status that wait() returns
+    assert_eq!(req.wait(&mut r#loop), Ok(0));
+    assert!(worked.get());
+    assert_eq!(status.get(), Some(Ok(0)));
+}
+
+#[test]
+fn fs_wait_returns_result() {
+    let mut r#loop = Loop::new().unwrap();
+    let result = Rc::new(Cell::new(None));
+
+    let on_open_result = result.clone();
+    let req = r#loop
+        .fs_open(
+            "Cargo.toml",
+            FsOpenFlags::RDONLY,
+            FsModeFlags::empty(),
+            move |req: FsReq| on_open_result.set(Some(req.result())),
+        )
+        .unwrap();
+
+    let file = req.wait(&mut r#loop).unwrap();
+    assert_eq!(result.get(), Some(Ok(file)));
+    r#loop.fs_close_sync(file as _).unwrap();
+}
+
+#[test]
+fn fs_wait_returns_error() {
+    let mut r#loop = Loop::new().unwrap();
+    let result = Rc::new(Cell::new(None));
+
+    let on_open_result = result.clone();
+    let req = r#loop
+        .fs_open(
+            "does-not-exist",
+            FsOpenFlags::RDONLY,
+            FsModeFlags::empty(),
+            move |req: FsReq| on_open_result.set(Some(req.result())),
+        )
+        .unwrap();
+
+    assert_eq!(req.wait(&mut r#loop), Err(libuv::Error::ENOENT));
+    assert_eq!(result.get(), Some(Err(libuv::Error::ENOENT)));
+}
+
+#[test]
+fn fs_wait_without_callback() {
+    let mut r#loop = Loop::new().unwrap();
+
+    // without a callback the request has already run synchronously, so wait() just returns its
+    // result
+    let req = r#loop
+        .fs_open("Cargo.toml", FsOpenFlags::RDONLY, FsModeFlags::empty(), ())
+        .unwrap();
+    let file = req.wait(&mut r#loop).unwrap();
+    r#loop.fs_close_sync(file as _).unwrap();
+}